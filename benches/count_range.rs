@@ -0,0 +1,43 @@
+use std::hint::black_box;
+
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use punch_clock::{Event, Sheet};
+
+/// The number of events in the benchmarked sheet.
+const EVENT_COUNT: i64 = 100_000;
+
+/// Create a sheet of one-hour events, each starting two hours after the last.
+fn large_sheet() -> Sheet {
+    let first = Utc.with_ymd_and_hms(2000, 1, 1, 9, 0, 0).unwrap();
+
+    Sheet::from_events_unchecked(
+        (0..EVENT_COUNT)
+            .map(|i| {
+                let start = first + Duration::hours(2 * i);
+                Event {
+                    stop: Some(start + Duration::hours(1)),
+                    ..Event::new(start)
+                }
+            })
+            .collect(),
+    )
+}
+
+fn count_range(c: &mut Criterion) {
+    let sheet = large_sheet();
+
+    // A week in the middle of the sheet.
+    let begin = sheet.events[EVENT_COUNT as usize / 2].start;
+    let end = begin + Duration::weeks(1);
+
+    c.bench_function("count_range", |b| {
+        b.iter(|| sheet.count_range(black_box(begin), black_box(end)))
+    });
+    c.bench_function("count_range_sorted", |b| {
+        b.iter(|| sheet.count_range_sorted(black_box(begin), black_box(end)))
+    });
+}
+
+criterion_group!(benches, count_range);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+//! Calculating earnings from recorded time.
+
+use chrono::Duration;
+
+/// An hourly rate of pay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rate {
+    /// The amount earned per hour.
+    pub amount: f64,
+    /// The currency that the amount is in, e.g. `"GBP"`. This is for presentation only, and is
+    /// not used in any calculations.
+    pub currency: String,
+}
+
+impl Rate {
+    /// Create a new hourly rate of pay.
+    pub fn new(amount: f64, currency: impl Into<String>) -> Self {
+        Rate {
+            amount,
+            currency: currency.into(),
+        }
+    }
+
+    /// Calculate the amount earned for working the given amount of time at this rate.
+    ///
+    /// The duration is converted to fractional hours with millisecond precision, and the result is
+    /// subject to the usual limits of floating-point arithmetic, so it should be rounded before
+    /// being presented as an amount of money.
+    pub fn earnings(&self, duration: Duration) -> f64 {
+        duration.num_milliseconds() as f64 / 3_600_000.0 * self.amount
+    }
+}
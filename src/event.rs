@@ -1,18 +1,229 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::sheet::SheetError;
 
 /// Represents a (possibly ongoing) period of time tracking, with its associated metadata.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Event {
     /// The start of a time-tracking period.
     pub start: DateTime<Utc>,
     /// The end of a time-tracking period.
     pub stop: Option<DateTime<Utc>>,
+    /// A note describing the work done during a time-tracking period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The project that the work done during a time-tracking period belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Free-form tags categorising the work done during a time-tracking period.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Whether the work done during a time-tracking period can be billed for.
+    #[serde(
+        default = "default_billable",
+        skip_serializing_if = "is_default_billable"
+    )]
+    pub billable: bool,
 }
 
 impl Event {
     /// Create a new event starting at the given time.
     pub fn new(start: DateTime<Utc>) -> Self {
-        Event { start, stop: None }
+        Event {
+            start,
+            stop: None,
+            description: None,
+            project: None,
+            tags: Vec::new(),
+            billable: default_billable(),
+        }
+    }
+
+    /// Create a new event starting at the given time, with a description of the work being done.
+    pub fn with_description(start: DateTime<Utc>, description: impl Into<String>) -> Self {
+        Event {
+            description: Some(description.into()),
+            ..Event::new(start)
+        }
+    }
+
+    /// Create a new event starting at the given time, belonging to the given project.
+    pub fn for_project(start: DateTime<Utc>, project: impl Into<String>) -> Self {
+        Event {
+            project: Some(project.into()),
+            ..Event::new(start)
+        }
+    }
+
+    /// Create a new event starting at the given time, with the given tags.
+    pub fn with_tags(start: DateTime<Utc>, tags: &[&str]) -> Self {
+        Event {
+            tags: tags.iter().map(|&tag| tag.to_owned()).collect(),
+            ..Event::new(start)
+        }
+    }
+
+    /// Get the length of this event, counting up to the current time if it is still ongoing.
+    pub fn duration(&self) -> Duration {
+        self.duration_at(Utc::now())
+    }
+
+    /// Get the duration of this event, as [`duration()`][duration] does, but as if the current
+    /// time were the given instant.
+    ///
+    /// [duration]: #method.duration
+    pub fn duration_at(&self, now: DateTime<Utc>) -> Duration {
+        self.stop.unwrap_or(now) - self.start
+    }
+
+    /// Check whether this event has been finished (i.e. has a stop time).
+    pub fn is_complete(&self) -> bool {
+        self.stop.is_some()
+    }
+
+    /// Check whether this event is still ongoing (i.e. has no stop time).
+    pub fn is_ongoing(&self) -> bool {
+        self.stop.is_none()
+    }
+
+    /// Check whether this event overlaps with another event, i.e. whether either event starts
+    /// after the other starts but before it stops.
+    ///
+    /// Ongoing events are treated as continuing indefinitely. Events that are merely adjacent (one
+    /// stopping at the same instant the other starts) do not overlap.
+    pub fn overlaps(&self, other: &Event) -> bool {
+        let starts_before_other_stops = other.stop.is_none_or(|stop| self.start < stop);
+        let other_starts_before_stop = self.stop.is_none_or(|stop| other.start < stop);
+
+        starts_before_other_stops && other_starts_before_stop
+    }
+
+    /// Check whether the given time falls within this event, i.e. at or after its start and before
+    /// its stop.
+    ///
+    /// Ongoing events are treated as continuing indefinitely.
+    pub fn contains_time(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && self.stop.is_none_or(|stop| time < stop)
+    }
+
+    /// Check whether this event has the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+impl TryFrom<Value> for Event {
+    type Error = SheetError;
+
+    /// Attempt to parse an event from a JSON value, in the same form as events in a sheet file.
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value).map_err(SheetError::ParseSheet)
+    }
+}
+
+impl From<Event> for Value {
+    /// Convert an event to a JSON value, in the same form as events in a sheet file.
+    fn from(event: Event) -> Self {
+        // Events only contain strings, booleans and times, which can always be represented in
+        // JSON.
+        serde_json::to_value(event).expect("events can always be serialized to JSON")
+    }
+}
+
+/// Events are billable unless marked otherwise, including events recorded before billability was
+/// tracked.
+pub(crate) fn default_billable() -> bool {
+    true
+}
+
+pub(crate) fn is_default_billable(billable: &bool) -> bool {
+    *billable == default_billable()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{completed, utc};
+
+    #[test]
+    fn completed_event() {
+        let event = completed(utc(9, 0), utc(10, 30));
+
+        assert_eq!(event.duration(), Duration::minutes(90));
+        assert_eq!(event.duration_at(utc(23, 0)), Duration::minutes(90));
+        assert!(event.is_complete());
+        assert!(!event.is_ongoing());
+    }
+
+    #[test]
+    fn ongoing_event() {
+        let event = Event::new(utc(9, 0));
+
+        assert_eq!(event.duration_at(utc(9, 45)), Duration::minutes(45));
+        assert!(!event.is_complete());
+        assert!(event.is_ongoing());
+
+        let started_now = Event::new(Utc::now() - Duration::hours(1));
+        assert!(started_now.duration() >= Duration::hours(1));
+    }
+
+    #[test]
+    fn zero_length_event() {
+        let event = completed(utc(9, 0), utc(9, 0));
+
+        assert_eq!(event.duration(), Duration::zero());
+        assert!(event.is_complete());
+        assert!(!event.is_ongoing());
+    }
+
+    #[test]
+    fn contains_time_at_boundaries() {
+        let event = completed(utc(9, 0), utc(10, 0));
+        let second = Duration::seconds(1);
+
+        assert!(!event.contains_time(utc(9, 0) - second));
+        assert!(event.contains_time(utc(9, 0)));
+        assert!(event.contains_time(utc(9, 30)));
+        assert!(event.contains_time(utc(10, 0) - second));
+        assert!(!event.contains_time(utc(10, 0)));
+        assert!(!event.contains_time(utc(10, 0) + second));
+    }
+
+    #[test]
+    fn ongoing_event_contains_every_later_time() {
+        let event = Event::new(utc(9, 0));
+
+        assert!(!event.contains_time(utc(9, 0) - Duration::seconds(1)));
+        assert!(event.contains_time(utc(9, 0)));
+        assert!(event.contains_time(utc(23, 59)));
+    }
+
+    #[test]
+    fn json_value_round_trips_all_fields() {
+        let event = Event {
+            stop: Some(utc(10, 30)),
+            description: Some("Write tests".to_owned()),
+            project: Some("punch-clock".to_owned()),
+            tags: vec!["dev".to_owned(), "tests".to_owned()],
+            billable: false,
+            ..Event::new(utc(9, 0))
+        };
+
+        let value = Value::from(event.clone());
+
+        assert_eq!(value["description"], "Write tests");
+        assert_eq!(Event::try_from(value).unwrap(), event);
+    }
+
+    #[test]
+    fn json_value_without_start_is_rejected() {
+        let value = serde_json::json!({ "stop": "2024-01-15T10:00:00Z" });
+
+        assert!(matches!(
+            Event::try_from(value),
+            Err(SheetError::ParseSheet(_))
+        ));
     }
 }
@@ -0,0 +1,111 @@
+use chrono::Duration;
+
+/// Ways in which a duration can be formatted for presentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DurationFormat {
+    /// Hours, minutes and seconds, as formatted by [`format_duration()`][format_duration], e.g.
+    /// `2h 15m 30s`.
+    ///
+    /// [format_duration]: ./fn.format_duration.html
+    HoursMinutesSeconds,
+    /// A decimal number of hours, as formatted by
+    /// [`format_duration_decimal()`][format_duration_decimal], e.g. `2.5h`.
+    ///
+    /// [format_duration_decimal]: ./fn.format_duration_decimal.html
+    DecimalHours,
+    /// A whole number of minutes, rounded down, e.g. `135m`.
+    Minutes,
+    /// A whole number of seconds, rounded down, e.g. `8130s`.
+    Seconds,
+}
+
+impl DurationFormat {
+    /// Format the given duration in this format.
+    pub fn format(self, duration: Duration) -> String {
+        match self {
+            DurationFormat::HoursMinutesSeconds => format_duration(duration),
+            DurationFormat::DecimalHours => format_duration_decimal(duration),
+            DurationFormat::Minutes => format!("{}m", duration.num_minutes()),
+            DurationFormat::Seconds => format!("{}s", duration.num_seconds()),
+        }
+    }
+}
+
+/// Format the given duration in hours, minutes and seconds, e.g. `2h 15m 30s`.
+///
+/// Components that are zero are omitted, except that a duration of less than a second is formatted
+/// as `0s`. Negative durations are prefixed with `-`.
+pub fn format_duration(duration: Duration) -> String {
+    let sign = if duration < Duration::zero() { "-" } else { "" };
+    let total_seconds = duration.num_seconds().unsigned_abs();
+
+    let hours = total_seconds / 3600;
+    let minutes = total_seconds / 60 % 60;
+    let seconds = total_seconds % 60;
+
+    let components: Vec<String> = [(hours, "h"), (minutes, "m"), (seconds, "s")]
+        .iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+
+    if components.is_empty() {
+        "0s".to_owned()
+    } else {
+        format!("{}{}", sign, components.join(" "))
+    }
+}
+
+/// Format the given duration as a decimal number of hours, to at most two decimal places, e.g.
+/// `2.5h`.
+pub fn format_duration_decimal(duration: Duration) -> String {
+    let hours = duration.num_seconds() as f64 / 3600.0;
+    let formatted = format!("{:.2}", hours);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" => "0h".to_owned(),
+        _ => format!("{}h", trimmed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_matrix() {
+        let cases = [
+            (Duration::zero(), "0s", "0h", "0m", "0s"),
+            (Duration::milliseconds(500), "0s", "0h", "0m", "0s"),
+            (Duration::seconds(45), "45s", "0.01h", "0m", "45s"),
+            (Duration::minutes(90), "1h 30m", "1.5h", "90m", "5400s"),
+            (Duration::hours(2), "2h", "2h", "120m", "7200s"),
+            (
+                Duration::seconds(8130),
+                "2h 15m 30s",
+                "2.26h",
+                "135m",
+                "8130s",
+            ),
+            (Duration::seconds(3605), "1h 5s", "1h", "60m", "3605s"),
+            (
+                Duration::minutes(-75),
+                "-1h 15m",
+                "-1.25h",
+                "-75m",
+                "-4500s",
+            ),
+        ];
+
+        for (duration, hms, decimal, minutes, seconds) in cases {
+            assert_eq!(DurationFormat::HoursMinutesSeconds.format(duration), hms);
+            assert_eq!(DurationFormat::DecimalHours.format(duration), decimal);
+            assert_eq!(DurationFormat::Minutes.format(duration), minutes);
+            assert_eq!(DurationFormat::Seconds.format(duration), seconds);
+
+            assert_eq!(format_duration(duration), hms);
+            assert_eq!(format_duration_decimal(duration), decimal);
+        }
+    }
+}
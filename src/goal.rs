@@ -0,0 +1,48 @@
+//! Tracking progress towards targets for the amount of time worked.
+
+use chrono::Duration;
+
+use crate::Period;
+
+/// A target amount of time to work during a period of time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Goal {
+    /// The amount of time to work.
+    pub target: Duration,
+    /// The period of time during which work counts towards the target.
+    pub period: Period,
+}
+
+/// How much of a [`Goal`][goal] has been met.
+///
+/// [goal]: ./struct.Goal.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GoalProgress {
+    /// The amount of time worked during the goal's period.
+    pub completed: Duration,
+    /// The amount of time still to work to meet the target, or zero if it has been met.
+    pub remaining: Duration,
+    /// The amount of time worked as a percentage of the target. This can exceed 100 if more time
+    /// has been worked than the target.
+    pub percentage: f64,
+}
+
+impl GoalProgress {
+    /// Calculate the progress towards the given target, having worked for the given amount of
+    /// time.
+    pub(crate) fn new(target: Duration, completed: Duration) -> Self {
+        let remaining = std::cmp::max(target - completed, Duration::zero());
+
+        let percentage = if target > Duration::zero() {
+            completed.num_milliseconds() as f64 / target.num_milliseconds() as f64 * 100.0
+        } else {
+            100.0
+        };
+
+        GoalProgress {
+            completed,
+            remaining,
+            percentage,
+        }
+    }
+}
@@ -3,10 +3,21 @@
 //! This library exposes an API for performing all the same tasks as through the command-line
 //! interface (e.g. punching in or out, checking time tracking status, counting totals).
 
+pub mod billing;
 mod event;
+mod format;
+pub mod goal;
+pub mod manager;
 mod period;
+pub mod rounding;
+pub mod schedule;
 pub mod sheet;
+pub mod storage;
+#[cfg(test)]
+mod testing;
+pub mod validation;
 
 pub use event::Event;
+pub use format::{format_duration, format_duration_decimal, DurationFormat};
 pub use period::Period;
 pub use sheet::Sheet;
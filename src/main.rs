@@ -1,11 +1,10 @@
 mod opt;
 
-use chrono::{prelude::*, Duration};
-use directories::ProjectDirs;
+use chrono::prelude::*;
 use opt::Opt;
 use punch_clock::{
     sheet::{SheetError, SheetStatus},
-    Period, Sheet,
+    Sheet,
 };
 use structopt::StructOpt;
 
@@ -15,20 +14,18 @@ const DIFF_DAY_FORMAT: &str = "%H:%M:%S on %e %b";
 fn main() {
     let opt = Opt::from_args();
 
-    // Try to load the sheet from the default location. If loading fails due to a missing file,
-    // create a new empty sheet.
-    let mut sheet = Sheet::load_default()
-        .or_else(|err| match err {
-            SheetError::OpenSheet(io_err) if io_err.raw_os_error() == Some(2) => {
-                Ok(Sheet::default())
-            }
-            _ => Err(err),
-        })
-        .unwrap();
+    // Try to load the sheet from the default location, or create a new empty sheet if the file
+    // doesn't exist yet.
+    let mut sheet = Sheet::load_or_create_default().unwrap();
 
     match opt {
-        Opt::In { .. } => match sheet.punch_in() {
+        Opt::In { time } => match sheet.punch_in_at(time.map_or_else(Utc::now, Into::into)) {
             Ok(time_utc) => {
+                // Try to write the sheet to the default location, creating the data directory if
+                // it doesn't exist yet. Only punching in or out changes the sheet, so the other
+                // subcommands leave the file alone.
+                sheet.write_default().unwrap();
+
                 let time_local: DateTime<Local> = time_utc.into();
 
                 println!("Punching in at {}.", time_local.format("%H:%M:%S"));
@@ -36,7 +33,7 @@ fn main() {
             Err(SheetError::PunchedIn(start_utc)) => {
                 let start_local: DateTime<Local> = start_utc.into();
 
-                let format = if start_local.date() == Local::today() {
+                let format = if start_local.date_naive() == Local::now().date_naive() {
                     SAME_DAY_FORMAT
                 } else {
                     DIFF_DAY_FORMAT
@@ -47,12 +44,20 @@ fn main() {
                     start_local.format(format)
                 );
             }
+            Err(err @ SheetError::FutureTime(_)) => {
+                println!("Can't punch in: {}.", err);
+            }
             Err(err) => {
                 panic!("Unexpected error while punching in: {}", err);
             }
         },
-        Opt::Out { .. } => match sheet.punch_out() {
+        Opt::Out { time } => match sheet.punch_out_at(time.map_or_else(Utc::now, Into::into)) {
             Ok(time_utc) => {
+                // Try to write the sheet to the default location, creating the data directory if
+                // it doesn't exist yet. Only punching in or out changes the sheet, so the other
+                // subcommands leave the file alone.
+                sheet.write_default().unwrap();
+
                 let time_local: DateTime<Local> = time_utc.into();
 
                 println!("Punching out at {}.", time_local.format("%H:%M:%S"));
@@ -60,7 +65,7 @@ fn main() {
             Err(SheetError::PunchedOut(end_utc)) => {
                 let end_local: DateTime<Local> = end_utc.into();
 
-                let format = if end_local.date() == Local::today() {
+                let format = if end_local.date_naive() == Local::now().date_naive() {
                     SAME_DAY_FORMAT
                 } else {
                     DIFF_DAY_FORMAT
@@ -74,6 +79,13 @@ fn main() {
             Err(SheetError::NoPunches) => {
                 println!("Can't punch out; no punch-in recorded.");
             }
+            Err(
+                err @ (SheetError::FutureTime(_)
+                | SheetError::InvalidTimeRange { .. }
+                | SheetError::OutOfOrder(_)),
+            ) => {
+                println!("Can't punch out: {}.", err);
+            }
             Err(err) => {
                 panic!("Unexpected error while punching out: {}", err);
             }
@@ -82,7 +94,7 @@ fn main() {
             SheetStatus::PunchedIn(start_utc) => {
                 let start_local: DateTime<Local> = start_utc.into();
 
-                let format = if start_local.date() == Local::today() {
+                let format = if start_local.date_naive() == Local::now().date_naive() {
                     SAME_DAY_FORMAT
                 } else {
                     DIFF_DAY_FORMAT
@@ -93,7 +105,7 @@ fn main() {
             SheetStatus::PunchedOut(end_utc) => {
                 let end_local: DateTime<Local> = end_utc.into();
 
-                let format = if end_local.date() == Local::today() {
+                let format = if end_local.date_naive() == Local::now().date_naive() {
                     SAME_DAY_FORMAT
                 } else {
                     DIFF_DAY_FORMAT
@@ -115,95 +127,7 @@ fn main() {
                     period.to_string().to_lowercase()
                 );
             } else {
-                let (start, end) = match period {
-                    Period::All => (sheet.events[0].start, Utc::now()),
-                    Period::Today => {
-                        let end_local = Local::now();
-                        let end_utc: DateTime<Utc> = end_local.into();
-                        let start_local = Local::today().and_hms(0, 0, 0);
-
-                        let span = end_local - start_local;
-                        let start_utc = end_utc - span;
-
-                        (start_utc, end_utc)
-                    }
-                    Period::Yesterday => {
-                        let end_local = Local::today().and_hms(0, 0, 0);
-                        let end_utc: DateTime<Utc> = end_local.into();
-                        let start_local = Local::today().pred().and_hms(0, 0, 0);
-
-                        let span = end_local - start_local;
-                        let start_utc = end_utc - span;
-
-                        (start_utc, end_utc)
-                    }
-                    Period::Week => {
-                        let mut last_monday = Local::today();
-                        while last_monday.weekday() != Weekday::Mon {
-                            last_monday = last_monday.pred();
-                        }
-
-                        let start_local = last_monday.and_hms(0, 0, 0);
-                        let end_local = Local::now();
-                        let end_utc: DateTime<Utc> = end_local.into();
-
-                        let span = end_local - start_local;
-                        let start_utc = end_utc - span;
-
-                        (start_utc, end_utc)
-                    }
-                    Period::LastWeek => {
-                        let mut last_monday = Local::today();
-                        while last_monday.weekday() != Weekday::Mon {
-                            last_monday = last_monday.pred();
-                        }
-
-                        let mut monday_before = last_monday.pred();
-                        while monday_before.weekday() != Weekday::Mon {
-                            monday_before = monday_before.pred();
-                        }
-
-                        let start_local = monday_before.and_hms(0, 0, 0);
-                        let end_local = last_monday.and_hms(0, 0, 0);
-                        let end_utc: DateTime<Utc> = end_local.into();
-
-                        let span = end_local - start_local;
-                        let start_utc = end_utc - span;
-
-                        (start_utc, end_utc)
-                    }
-                    Period::Month => {
-                        let now = Local::now();
-                        let month_first = Local.ymd(now.year(), now.month(), 1);
-
-                        let start_local = month_first.and_hms(0, 0, 0);
-                        let end_local = now;
-                        let end_utc: DateTime<Utc> = end_local.into();
-
-                        let span = end_local - start_local;
-                        let start_utc = end_utc - span;
-
-                        (start_utc, end_utc)
-                    }
-                    Period::LastMonth => {
-                        let today = Local::today();
-                        let month_first = Local.ymd(today.year(), today.month(), 1);
-
-                        let day_before = month_first - Duration::days(1);
-                        let last_month_first = Local.ymd(day_before.year(), day_before.month(), 1);
-
-                        let start_local = last_month_first.and_hms(0, 0, 0);
-                        let end_local = month_first.and_hms(0, 0, 0);
-                        let end_utc: DateTime<Utc> = end_local.into();
-
-                        let span = end_local - start_local;
-                        let start_utc = end_utc - span;
-
-                        (start_utc, end_utc)
-                    }
-                };
-
-                let total = sheet.count_range(start, end);
+                let total = sheet.count_period(&period);
 
                 println!(
                     "Time worked {}: {} hours, {} minutes.",
@@ -214,22 +138,4 @@ fn main() {
             }
         }
     }
-
-    // Try to write the sheet to the default location. If loading fails due to a missing directory,
-    // create the directory.
-    sheet
-        .write_default()
-        .or_else(|err| match err {
-            SheetError::WriteSheet(io_err) if io_err.raw_os_error() == Some(2) => {
-                let dd = ProjectDirs::from("dev", "neros", "PunchClock")
-                    .expect("Unable to locate data directory for punch-clock.")
-                    .data_dir()
-                    .to_owned();
-
-                std::fs::create_dir(dd).expect("Unable to create data directory for punch-clock.");
-                sheet.write_default()
-            }
-            _ => Err(err),
-        })
-        .unwrap();
 }
@@ -0,0 +1,226 @@
+//! Managing a collection of named timesheets stored in the same directory.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDateTime;
+
+use crate::{
+    sheet::{SheetError, BACKUP_SUFFIX_FORMAT},
+    Sheet,
+};
+
+/// A collection of named sheets, each stored as a JSON file in the same directory.
+///
+/// The file for a sheet is named after the sheet, so the sheet named `work` is stored in
+/// `work.json`. Names must be non-empty, and can't contain path separators or `..`, so that every
+/// sheet is stored directly in the manager's directory. Names also can't end in a date and time
+/// like the backups made by [`Sheet::backup_file()`][backup_file], such as
+/// `work-2020-01-31-093000`, so that backups aren't mistaken for sheets.
+///
+/// [backup_file]: ../sheet/struct.Sheet.html#method.backup_file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SheetManager {
+    dir: PathBuf,
+    sheets: HashMap<String, Sheet>,
+}
+
+impl SheetManager {
+    /// Create a new manager with no sheets, storing sheets in the given directory.
+    pub fn new(dir: &Path) -> Self {
+        SheetManager {
+            dir: dir.to_owned(),
+            sheets: HashMap::new(),
+        }
+    }
+
+    /// Attempt to load every `.json` file in the given directory as a sheet, named after the
+    /// file.
+    ///
+    /// Files that aren't named after a valid sheet name, such as backups made by
+    /// [`Sheet::backup_file()`][backup_file], are skipped.
+    ///
+    /// [backup_file]: ../sheet/struct.Sheet.html#method.backup_file
+    pub fn load_all(dir: &Path) -> Result<SheetManager, SheetError> {
+        let mut manager = Self::new(dir);
+
+        for entry in fs::read_dir(dir).map_err(SheetError::ReadSheetDir)? {
+            let path = entry.map_err(SheetError::ReadSheetDir)?.path();
+
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && path.is_file()
+            {
+                let name = match path.file_stem().and_then(|name| name.to_str()) {
+                    Some(name) if check_name(name).is_ok() => name,
+                    _ => continue,
+                };
+
+                let sheet = Sheet::load_from_path(&path)?;
+                manager.sheets.insert(name.to_owned(), sheet);
+            }
+        }
+
+        Ok(manager)
+    }
+
+    /// Get the directory that sheets are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Attempt to get the path to the file that the sheet with the given name is stored in, failing
+    /// with [`SheetError::InvalidSheetName`][invalid] if the name isn't valid.
+    ///
+    /// [invalid]: ../sheet/enum.SheetError.html#variant.InvalidSheetName
+    pub fn path_for(&self, name: &str) -> Result<PathBuf, SheetError> {
+        check_name(name)?;
+
+        Ok(sheet_path(&self.dir, name))
+    }
+
+    /// Iterate over the names of all the sheets, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sheets.keys().map(String::as_str)
+    }
+
+    /// Get the sheet with the given name, if there is one.
+    pub fn get(&self, name: &str) -> Option<&Sheet> {
+        self.sheets.get(name)
+    }
+
+    /// Get a mutable reference to the sheet with the given name, if there is one.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Sheet> {
+        self.sheets.get_mut(name)
+    }
+
+    /// Attempt to add a sheet with the given name, returning the sheet it replaced if there was
+    /// already one with that name, or failing with
+    /// [`SheetError::InvalidSheetName`][invalid] if the name isn't valid.
+    ///
+    /// [invalid]: ../sheet/enum.SheetError.html#variant.InvalidSheetName
+    pub fn insert(&mut self, name: &str, sheet: Sheet) -> Result<Option<Sheet>, SheetError> {
+        check_name(name)?;
+
+        Ok(self.sheets.insert(name.to_owned(), sheet))
+    }
+
+    /// Remove the sheet with the given name, returning it if there was one.
+    ///
+    /// The sheet's file is left in place, so it will be loaded again by
+    /// [`load_all()`][load_all] unless deleted separately.
+    ///
+    /// [load_all]: #method.load_all
+    pub fn remove(&mut self, name: &str) -> Option<Sheet> {
+        self.sheets.remove(name)
+    }
+
+    /// Attempt to write every sheet to its file, creating the directory first if it doesn't exist
+    /// yet.
+    pub fn save_all(&mut self) -> Result<(), SheetError> {
+        fs::create_dir_all(&self.dir).map_err(SheetError::CreateDataDir)?;
+
+        for (name, sheet) in &mut self.sheets {
+            sheet.write_to_path(&sheet_path(&self.dir, name))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the path to the file in the given directory that the sheet with the given name is stored in.
+fn sheet_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name))
+}
+
+/// Check that the given name can be used for a sheet, as described in the documentation for
+/// [`SheetManager`][manager].
+///
+/// [manager]: ./struct.SheetManager.html
+fn check_name(name: &str) -> Result<(), SheetError> {
+    let is_path = name.is_empty()
+        || name.contains(['/', '\\', std::path::MAIN_SEPARATOR])
+        || name.contains("..");
+
+    if is_path || is_backup_name(name) {
+        Err(SheetError::InvalidSheetName(name.to_owned()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether the given name ends in a date and time in the format used for the names of backups.
+fn is_backup_name(name: &str) -> bool {
+    // The suffix is always a dash followed by 17 digits and dashes, like `-2020-01-31-093000`.
+    const SUFFIX_LEN: usize = 18;
+
+    name.len()
+        .checked_sub(SUFFIX_LEN)
+        .and_then(|start| name.get(start..))
+        .is_some_and(|suffix| NaiveDateTime::parse_from_str(suffix, BACKUP_SUFFIX_FORMAT).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        let mut manager = SheetManager::new(Path::new("sheets"));
+
+        for name in ["", "a/b", "a\\b", "..", "../work", "work.."] {
+            assert!(matches!(
+                manager.insert(name, Sheet::default()),
+                Err(SheetError::InvalidSheetName(ref invalid)) if invalid == name
+            ));
+            assert!(matches!(
+                manager.path_for(name),
+                Err(SheetError::InvalidSheetName(_))
+            ));
+        }
+
+        assert_eq!(manager.names().count(), 0);
+    }
+
+    #[test]
+    fn backup_names_are_rejected() {
+        let mut manager = SheetManager::new(Path::new("sheets"));
+
+        assert!(matches!(
+            manager.insert("work-2020-01-31-093000", Sheet::default()),
+            Err(SheetError::InvalidSheetName(_))
+        ));
+        assert!(manager.insert("work-2020", Sheet::default()).is_ok());
+    }
+
+    #[test]
+    fn valid_names_are_stored_in_dir() {
+        let mut manager = SheetManager::new(Path::new("sheets"));
+
+        assert!(manager.insert("work", Sheet::default()).unwrap().is_none());
+        assert_eq!(
+            manager.path_for("work").unwrap(),
+            Path::new("sheets").join("work.json")
+        );
+    }
+
+    #[test]
+    fn load_all_skips_backups() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut manager = SheetManager::new(dir.path());
+        manager.insert("work", Sheet::named("work")).unwrap();
+        manager.save_all().unwrap();
+
+        Sheet::backup_file(&manager.path_for("work").unwrap()).unwrap();
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        let loaded = SheetManager::load_all(dir.path()).unwrap();
+        assert_eq!(loaded.names().collect::<Vec<_>>(), vec!["work"]);
+        assert_eq!(loaded.get("work"), manager.get("work"));
+    }
+}
@@ -7,15 +7,15 @@ use structopt::StructOpt;
 pub enum Opt {
     /// Start tracking time.
     In {
-        /// The time to start the tracking period from (default: now). Currently unimplemented;
-        /// always defaults to now.
+        /// The time to start the tracking period from, such as "2024-01-15T09:00:00+01:00"
+        /// (default: now).
         #[structopt(short = "t", long = "time")]
         time: Option<DateTime<Local>>,
     },
     /// Stop tracking time.
     Out {
-        /// The time to end the tracking period at (default: now). Currently unimplemented; always
-        /// defaults to now.
+        /// The time to end the tracking period at, such as "2024-01-15T17:00:00+01:00" (default:
+        /// now).
         #[structopt(short = "t", long = "time")]
         time: Option<DateTime<Local>>,
     },
@@ -24,8 +24,8 @@ pub enum Opt {
     /// Count the amount of time worked over a certain period of time.
     Count {
         /// Period of time to count from. Values for <period> include: all, today, yesterday, week,
-        /// month, last week, last month. Shortened versions of these values are also available,
-        /// such as "t" for "today".
+        /// month, year, last week, last month. Shortened versions of these values are also
+        /// available, such as "t" for "today".
         #[structopt(default_value = "today")]
         period: Period,
     },
@@ -3,6 +3,10 @@ use std::{
     str::FromStr,
 };
 
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::format::format_duration;
+
 /// Represents a period of time relative to now.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Period {
@@ -25,6 +29,62 @@ pub enum Period {
     /// The period of time between the midnights at the beginning of the last two occurrences of
     /// days whose numbers were 1 (including the current day).
     LastMonth,
+    /// The period of time that began at midnight at the start of the 1st of January of the current
+    /// year.
+    Year,
+    /// The period of time of the given length that ends now.
+    Last(Duration),
+    /// The period of time between the two given instants.
+    Custom(DateTime<Utc>, DateTime<Utc>),
+}
+
+impl Period {
+    /// Get the instants at which this period of time begins and ends, with calendar days
+    /// determined in local time.
+    ///
+    /// Periods that do not end at a particular midnight end at the current time. The beginning of
+    /// [`Period::All`][all] is the earliest representable instant.
+    ///
+    /// [all]: #variant.All
+    pub fn to_range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        self.to_range_at(Utc::now())
+    }
+
+    /// Get the instants at which this period of time begins and ends, as
+    /// [`to_range()`][to_range] does, but as if the current time were the given instant.
+    ///
+    /// [to_range]: #method.to_range
+    pub fn to_range_at(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let today = now.with_timezone(&Local).date_naive();
+        let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+        let month_first = today.with_day(1).unwrap();
+
+        match self {
+            Period::All => (DateTime::<Utc>::MIN_UTC, now),
+            Period::Today => (local_midnight(today), now),
+            Period::Yesterday => (
+                local_midnight(today.pred_opt().unwrap()),
+                local_midnight(today),
+            ),
+            Period::Week => (local_midnight(monday), now),
+            Period::LastWeek => (
+                local_midnight(monday - Duration::days(7)),
+                local_midnight(monday),
+            ),
+            Period::Month => (local_midnight(month_first), now),
+            Period::LastMonth => {
+                let day_before = month_first.pred_opt().unwrap();
+
+                (
+                    local_midnight(day_before.with_day(1).unwrap()),
+                    local_midnight(month_first),
+                )
+            }
+            Period::Year => (local_midnight(today.with_ordinal(1).unwrap()), now),
+            Period::Last(length) => (now - *length, now),
+            Period::Custom(begin, end) => (*begin, *end),
+        }
+    }
 }
 
 impl FromStr for Period {
@@ -39,6 +99,7 @@ impl FromStr for Period {
             "last week" | "lastweek" | "lw" => Ok(Period::LastWeek),
             "month" | "this month" | "m" | "tm" => Ok(Period::Month),
             "last month" | "lastmonth" | "lm" => Ok(Period::LastMonth),
+            "year" | "this year" | "ty" => Ok(Period::Year),
             _ => Err("Time period not recognised.".into()),
         }
     }
@@ -54,6 +115,31 @@ impl Display for Period {
             Period::LastWeek => write!(f, "Last Week"),
             Period::Month => write!(f, "This Month"),
             Period::LastMonth => write!(f, "Last Month"),
+            Period::Year => write!(f, "This Year"),
+            Period::Last(length) => write!(f, "In The Last {}", format_duration(*length)),
+            Period::Custom(begin, end) => write!(f, "From {} To {}", begin, end),
         }
     }
 }
+
+/// Get the instant of midnight at the start of the given day in local time.
+///
+/// If midnight happens twice on the given day (e.g. due to a daylight saving transition), the
+/// earlier instant is used.
+pub(crate) fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    local_instant(date, NaiveTime::MIN)
+}
+
+/// Get the instant at which it is the given time on the given day in local time.
+///
+/// If the time happens twice on the given day (e.g. due to a daylight saving transition), the
+/// earlier instant is used. If it doesn't happen at all, the same time in UTC is used instead.
+pub(crate) fn local_instant(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let datetime = date.and_time(time);
+
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(Into::into)
+        .unwrap_or_else(|| Utc.from_utc_datetime(&datetime))
+}
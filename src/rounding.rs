@@ -0,0 +1,84 @@
+//! Rounding recorded times to fixed increments.
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+/// Ways in which times can be rounded to an increment of a whole number of minutes.
+///
+/// Increments are counted from midnight UTC, so e.g. 15-minute increments fall on the hour and at
+/// quarter past, half past and quarter to the hour. An increment of zero minutes leaves times
+/// unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to the nearest increment, rounding halfway times up.
+    NearestMinutes(u32),
+    /// Round up to the next increment, unless already on one.
+    CeilMinutes(u32),
+    /// Round down to the previous increment, unless already on one.
+    FloorMinutes(u32),
+}
+
+impl RoundingMode {
+    /// Round the given time according to this mode.
+    pub fn round(self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let (minutes, round): (u32, fn(DateTime<Utc>, Duration) -> _) = match self {
+            RoundingMode::NearestMinutes(minutes) => (minutes, DurationRound::duration_round),
+            RoundingMode::CeilMinutes(minutes) => (minutes, DurationRound::duration_round_up),
+            RoundingMode::FloorMinutes(minutes) => (minutes, DurationRound::duration_trunc),
+        };
+
+        if minutes == 0 {
+            time
+        } else {
+            round(time, Duration::minutes(minutes.into())).unwrap_or(time)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// The given time on Monday 2024-01-15, in UTC.
+    fn utc(hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 15, hour, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn ceil_to_quarter_hour() {
+        let mode = RoundingMode::CeilMinutes(15);
+
+        assert_eq!(mode.round(utc(9, 0, 0)), utc(9, 0, 0));
+        assert_eq!(mode.round(utc(9, 0, 1)), utc(9, 15, 0));
+        assert_eq!(mode.round(utc(9, 14, 59)), utc(9, 15, 0));
+        assert_eq!(mode.round(utc(9, 50, 0)), utc(10, 0, 0));
+    }
+
+    #[test]
+    fn floor_to_quarter_hour() {
+        let mode = RoundingMode::FloorMinutes(15);
+
+        assert_eq!(mode.round(utc(9, 0, 0)), utc(9, 0, 0));
+        assert_eq!(mode.round(utc(9, 14, 59)), utc(9, 0, 0));
+        assert_eq!(mode.round(utc(9, 15, 0)), utc(9, 15, 0));
+        assert_eq!(mode.round(utc(9, 59, 59)), utc(9, 45, 0));
+    }
+
+    #[test]
+    fn nearest_quarter_hour() {
+        let mode = RoundingMode::NearestMinutes(15);
+
+        assert_eq!(mode.round(utc(9, 7, 29)), utc(9, 0, 0));
+        assert_eq!(mode.round(utc(9, 7, 30)), utc(9, 15, 0));
+    }
+
+    #[test]
+    fn zero_increment_leaves_time_unchanged() {
+        assert_eq!(
+            RoundingMode::CeilMinutes(0).round(utc(9, 7, 3)),
+            utc(9, 7, 3)
+        );
+    }
+}
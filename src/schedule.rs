@@ -0,0 +1,74 @@
+//! Describing regular working hours.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, Utc, Weekday};
+
+use crate::period::local_instant;
+
+/// The hours of the day, and days of the week, during which work is scheduled, in local time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WorkSchedule {
+    /// The time of day at which work starts.
+    pub start_time: NaiveTime,
+    /// The time of day at which work ends. If this is not after `start_time`, work ends at this
+    /// time on the following day.
+    pub end_time: NaiveTime,
+    /// Whether work is scheduled on each day of the week, starting from Monday.
+    pub work_days: [bool; 7],
+}
+
+impl WorkSchedule {
+    /// Create a new schedule of work between the given times from Monday to Friday.
+    pub fn weekdays(start_time: NaiveTime, end_time: NaiveTime) -> Self {
+        WorkSchedule {
+            start_time,
+            end_time,
+            work_days: [true, true, true, true, true, false, false],
+        }
+    }
+
+    /// Check whether work is scheduled on the given day of the week.
+    pub fn is_work_day(&self, weekday: Weekday) -> bool {
+        self.work_days[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Get the periods of scheduled work that fall within the range between the two given
+    /// instants, in chronological order, as pairs of the instants at which each begins and ends.
+    ///
+    /// Periods that extend past either end of the range are clipped to the range.
+    pub(crate) fn windows_within(
+        &self,
+        begin: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut windows = Vec::new();
+
+        if end <= begin {
+            return windows;
+        }
+
+        // Start from the day before, in case work scheduled then runs past midnight.
+        let mut day = begin.with_timezone(&Local).date_naive().pred_opt().unwrap();
+        let last_day = end.with_timezone(&Local).date_naive();
+
+        while day <= last_day {
+            if self.is_work_day(day.weekday()) {
+                let end_day = if self.end_time <= self.start_time {
+                    day.succ_opt().unwrap()
+                } else {
+                    day
+                };
+
+                let window_begin = std::cmp::max(begin, local_instant(day, self.start_time));
+                let window_end = std::cmp::min(end, local_instant(end_day, self.end_time));
+
+                if window_begin < window_end {
+                    windows.push((window_begin, window_end));
+                }
+            }
+
+            day = day.succ_opt().unwrap();
+        }
+
+        windows
+    }
+}
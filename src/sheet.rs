@@ -1,56 +1,276 @@
 //! Working with recorded timesheets (lists of events).
 
 use std::{
-    fs::File,
-    io::{Read, Write},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ffi::{OsStr, OsString},
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::{self, File},
+    hash::{Hash, Hasher},
+    io::{ErrorKind, Read, Write},
+    ops::{Add, AddAssign},
     path::{Path, PathBuf},
 };
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
 use directories::ProjectDirs;
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::Event;
+use crate::{
+    billing::Rate,
+    format::{format_duration, DurationFormat},
+    goal::{Goal, GoalProgress},
+    period::local_midnight,
+    rounding::RoundingMode,
+    schedule::WorkSchedule,
+    storage::{FileBackend, StorageBackend},
+    validation::{ValidationError, ValidationRule},
+    Event, Period,
+};
+
+/// The default for how far into the future punch times are allowed to be, in seconds.
+const DEFAULT_FUTURE_TOLERANCE_SECS: i64 = 60;
+
+/// The format of the local date and time appended to the names of backup files.
+pub(crate) const BACKUP_SUFFIX_FORMAT: &str = "-%Y-%m-%d-%H%M%S";
+
+/// The headings of the columns in tables of the events in a sheet.
+const TABLE_HEADINGS: [&str; 5] = ["Date", "Start", "Stop", "Duration", "Description"];
 
 /// List of events, together comprising a log of work from which totals can be calculated for
 /// various periods of time.
-#[derive(Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+///
+/// When serialized, times are written as [RFC 3339][rfc3339] strings in UTC, such as
+/// `"2024-01-15T09:00:00Z"`, with fractional seconds if there are any. See
+/// [`to_epoch_json()`][epoch] for a more compact alternative.
+///
+/// Sheets are compared, ordered and hashed by their events, names and descriptions, ignoring the
+/// times they were created and last written, and settings like the future tolerance.
+///
+/// [rfc3339]: https://tools.ietf.org/html/rfc3339
+/// [epoch]: #method.to_epoch_json
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Sheet {
     pub events: Vec<Event>,
+    /// A name identifying what the sheet tracks time for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// A longer description of what the sheet tracks time for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// When the sheet was created, or `None` for sheets created before this was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    /// When the sheet was last written, or `None` if it never has been.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+    /// How far into the future punch times are allowed to be, or `None` for the default.
+    #[serde(skip)]
+    future_tolerance: Option<Duration>,
 }
 
 impl Sheet {
     /// Attempt to load a sheet from the file at the default location, as determined by
     /// [`default_loc()`][default].
     ///
+    /// While the sheet is being loaded, a shared lock is held on a lock file in the same
+    /// directory, waiting for any process writing the default sheet to finish first.
+    ///
     /// [default]: #method.default_loc
     pub fn load_default() -> Result<Sheet, SheetError> {
-        Self::load(Self::default_loc()?)
+        let _lock = lock_default_dir(LockMode::Shared)?;
+        Self::load_with(&FileBackend(Self::default_loc()?))
+    }
+
+    /// Attempt to load a sheet from the file at the default location, as with
+    /// [`load_default()`][load_default], but fail with
+    /// [`SheetError::SheetLocked`][locked] rather than waiting if another process is writing the
+    /// default sheet.
+    ///
+    /// [load_default]: #method.load_default
+    /// [locked]: ./enum.SheetError.html#variant.SheetLocked
+    pub fn try_load_default() -> Result<Sheet, SheetError> {
+        let _lock = lock_default_dir(LockMode::TryShared)?;
+        Self::load_with(&FileBackend(Self::default_loc()?))
+    }
+
+    /// Attempt to load a sheet from the given storage backend.
+    pub fn load_with(backend: &dyn StorageBackend) -> Result<Sheet, SheetError> {
+        backend.load()
+    }
+
+    /// Attempt to load a sheet from the file at the default location, as determined by
+    /// [`default_loc()`][default], or create a new empty sheet if the file doesn't exist yet.
+    ///
+    /// As with [`load_default()`][load_default], a shared lock is held while the sheet is loaded.
+    ///
+    /// [default]: #method.default_loc
+    /// [load_default]: #method.load_default
+    pub fn load_or_create_default() -> Result<Sheet, SheetError> {
+        let _lock = lock_default_dir(LockMode::Shared)?;
+        Self::load_or_create_at(&Self::default_loc()?)
+    }
+
+    /// Attempt to load a sheet from the file at the given path, or create a new empty sheet if the
+    /// file doesn't exist yet.
+    pub fn load_or_create_at(path: &Path) -> Result<Sheet, SheetError> {
+        match Self::load_from_path(path) {
+            Err(SheetError::OpenSheet(err)) if err.kind() == ErrorKind::NotFound => {
+                Ok(Sheet::default())
+            }
+            result => result,
+        }
     }
 
     /// Attempt to load a sheet from the file at the given path.
     pub fn load<P>(path: P) -> Result<Sheet, SheetError>
     where
         P: AsRef<Path>,
+    {
+        Self::load_from_path(path.as_ref())
+    }
+
+    /// Attempt to load a sheet from the file at the given path.
+    pub fn load_from_path(path: &Path) -> Result<Sheet, SheetError> {
+        let sheet_file = File::open(path).map_err(SheetError::OpenSheet)?;
+
+        Self::load_from_reader(sheet_file)
+    }
+
+    /// Attempt to load a sheet from the file at the default location, as determined by
+    /// [`default_loc()`][default], without blocking.
+    ///
+    /// Unlike [`load_default()`][load_default], this doesn't lock the default sheet.
+    ///
+    /// [default]: #method.default_loc
+    /// [load_default]: #method.load_default
+    #[cfg(feature = "tokio")]
+    pub async fn load_default_async() -> Result<Sheet, SheetError> {
+        Self::load_from_path_async(&Self::default_loc()?).await
+    }
+
+    /// Attempt to load a sheet from the file at the given path, without blocking.
+    #[cfg(feature = "tokio")]
+    pub async fn load_from_path_async(path: &Path) -> Result<Sheet, SheetError> {
+        use tokio::io::AsyncReadExt;
+
+        let mut sheet_json = String::new();
+
+        tokio::fs::File::open(path)
+            .await
+            .map_err(SheetError::OpenSheet)?
+            .read_to_string(&mut sheet_json)
+            .await
+            .map_err(SheetError::ReadSheet)?;
+
+        Self::from_json_str(&sheet_json)
+    }
+
+    /// Attempt to load a sheet from the given reader.
+    ///
+    /// If the reader contains no data at all, an empty sheet is returned.
+    pub fn load_from_reader<R>(mut reader: R) -> Result<Sheet, SheetError>
+    where
+        R: Read,
     {
         let mut sheet_json = String::new();
 
-        {
-            let mut sheet_file = File::open(&path).map_err(SheetError::OpenSheet)?;
+        reader
+            .read_to_string(&mut sheet_json)
+            .map_err(SheetError::ReadSheet)?;
 
-            sheet_file
-                .read_to_string(&mut sheet_json)
-                .map_err(SheetError::ReadSheet)?;
-        }
+        Self::from_json_str(&sheet_json)
+    }
 
+    /// Attempt to parse a sheet from the given JSON string.
+    ///
+    /// If the string is empty, an empty sheet is returned.
+    pub fn from_json_str(sheet_json: &str) -> Result<Sheet, SheetError> {
         if sheet_json.is_empty() {
             Ok(Sheet::default())
         } else {
-            serde_json::from_str(&sheet_json).map_err(SheetError::ParseSheet)
+            serde_json::from_str(sheet_json).map_err(SheetError::ParseSheet)
         }
     }
 
+    /// Attempt to load a sheet from the TOML file at the given path.
+    ///
+    /// If the file is empty, an empty sheet is returned.
+    #[cfg(feature = "toml")]
+    pub fn load_from_toml_path(path: &Path) -> Result<Sheet, SheetError> {
+        let mut sheet_toml = String::new();
+
+        File::open(path)
+            .map_err(SheetError::OpenSheet)?
+            .read_to_string(&mut sheet_toml)
+            .map_err(SheetError::ReadSheet)?;
+
+        if sheet_toml.is_empty() {
+            Ok(Sheet::default())
+        } else {
+            toml::from_str(&sheet_toml).map_err(SheetError::ParseTomlSheet)
+        }
+    }
+
+    /// Attempt to load a sheet from the file at the given path, in the format indicated by the
+    /// file's extension: JSON for `.json`, or TOML for `.toml` if the `toml` feature is enabled.
+    pub fn from_file_detecting_format(path: &Path) -> Result<Sheet, SheetError> {
+        match format_extension(path).as_str() {
+            "json" => Self::load_from_path(path),
+            #[cfg(feature = "toml")]
+            "toml" => Self::load_from_toml_path(path),
+            extension => Err(SheetError::UnknownFormat(extension.to_owned())),
+        }
+    }
+
+    /// Attempt to load a sheet from CSV data in the given reader.
+    ///
+    /// The data must have a header row naming its columns, of which only `start` is required. The
+    /// other recognised columns are `stop` (empty for an ongoing event), `project`, `description`
+    /// and `tags` (separated by semicolons), in the format written by
+    /// [`to_csv_writer()`][to_csv_writer].
+    ///
+    /// [to_csv_writer]: #method.to_csv_writer
+    #[cfg(feature = "csv")]
+    pub fn from_csv_reader<R>(reader: R) -> Result<Sheet, SheetError>
+    where
+        R: Read,
+    {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+
+        csv_reader
+            .deserialize()
+            .map(|record| record.map(CsvEvent::into_event))
+            .collect::<Result<_, _>>()
+            .map_err(SheetError::ParseCsv)
+    }
+
+    /// Attempt to load a sheet from CSV data in the given reader, in the format exported by
+    /// [Toggl][toggl].
+    ///
+    /// The data must have a header row naming its columns. The `Start date` and `Start time`
+    /// columns are required, and are interpreted in local time, as are `End date` and `End time`.
+    /// Rows without an end time are loaded as ongoing events. The `Project`, `Description` and
+    /// `Billable` columns are loaded into the corresponding fields of each event, and any other
+    /// columns are ignored.
+    ///
+    /// [toggl]: https://toggl.com
+    #[cfg(feature = "csv")]
+    pub fn from_toggl_csv_reader<R>(reader: R) -> Result<Sheet, SheetError>
+    where
+        R: Read,
+    {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+
+        csv_reader
+            .deserialize()
+            .map(|record| record.map(TogglEvent::into_event))
+            .collect::<Result<_, _>>()
+            .map_err(SheetError::ParseCsv)
+    }
+
     /// Get the default directory in which sheets are stored.
     ///
     /// The directory is determined using the [directories][directories] crate by platform as
@@ -83,24 +303,458 @@ impl Sheet {
     /// Attempt to write a sheet to the file at the default location, as determined by
     /// [`default_loc()`][default].
     ///
+    /// The directory returned from [`default_dir()`][default_dir] is created first if it doesn't
+    /// exist yet.
+    ///
+    /// While the sheet is being written, an exclusive lock is held on a lock file in the same
+    /// directory, so that other processes can't load or write the default sheet at the same time.
+    ///
+    /// [default]: #method.default_loc
+    /// [default_dir]: #method.default_dir
+    pub fn write_default(&mut self) -> Result<(), SheetError> {
+        Self::ensure_data_dir()?;
+        let _lock = lock_default_dir(LockMode::Exclusive)?;
+        self.write_with(&FileBackend(Self::default_loc()?))
+    }
+
+    /// Attempt to write a sheet to the file at the default location, as with
+    /// [`write_default()`][write_default], first backing up the existing file with
+    /// [`backup_default()`][backup_default] if there is one.
+    ///
+    /// Returns the path to the backup, if one was made.
+    ///
+    /// [write_default]: #method.write_default
+    /// [backup_default]: #method.backup_default
+    pub fn write_default_with_backup(&mut self) -> Result<Option<PathBuf>, SheetError> {
+        Self::ensure_data_dir()?;
+        let _lock = lock_default_dir(LockMode::Exclusive)?;
+        self.write_to_path_with_backup(&Self::default_loc()?)
+    }
+
+    /// Attempt to copy the file at the default location, as determined by
+    /// [`default_loc()`][default], to a backup file in the same directory.
+    ///
+    /// See [`backup_file()`][backup_file] for how the backup file is named.
+    ///
     /// [default]: #method.default_loc
-    pub fn write_default(&self) -> Result<(), SheetError> {
-        self.write(Self::default_loc()?)
+    /// [backup_file]: #method.backup_file
+    pub fn backup_default() -> Result<PathBuf, SheetError> {
+        Self::backup_file(&Self::default_loc()?)
+    }
+
+    /// Attempt to copy the sheet file at the given path to a backup file in the same directory,
+    /// returning the path to the backup.
+    ///
+    /// The backup file is named after the original with the current local date and time appended,
+    /// so that a backup of `sheet.json` is named like `sheet-2020-01-31-093000.json`.
+    pub fn backup_file(path: &Path) -> Result<PathBuf, SheetError> {
+        let stem = path.file_stem().unwrap_or_else(|| OsStr::new("sheet"));
+
+        let mut backup_name = OsString::from(stem);
+        backup_name.push(Local::now().format(BACKUP_SUFFIX_FORMAT).to_string());
+        if let Some(extension) = path.extension() {
+            backup_name.push(".");
+            backup_name.push(extension);
+        }
+
+        let backup_path = path.with_file_name(backup_name);
+        fs::copy(path, &backup_path).map_err(SheetError::BackupSheet)?;
+
+        Ok(backup_path)
+    }
+
+    /// Attempt to write a sheet to the given storage backend.
+    pub fn write_with(&mut self, backend: &dyn StorageBackend) -> Result<(), SheetError> {
+        self.record_write(|sheet| backend.store(sheet))
+    }
+
+    /// Create the directory returned from [`default_dir()`][default], and any missing parent
+    /// directories, if it doesn't exist yet.
+    ///
+    /// [default]: #method.default_dir
+    pub fn ensure_data_dir() -> Result<(), SheetError> {
+        fs::create_dir_all(Self::default_dir()?).map_err(SheetError::CreateDataDir)
     }
 
     /// Attempt to write a sheet to the file at the given path.
-    pub fn write<P>(&self, path: P) -> Result<(), SheetError>
+    pub fn write<P>(&mut self, path: P) -> Result<(), SheetError>
     where
         P: AsRef<Path>,
     {
-        let new_sheet_json = serde_json::to_string(self).unwrap();
+        self.write_to_path(path.as_ref())
+    }
+
+    /// Attempt to write a sheet to the file at the given path.
+    ///
+    /// The sheet is first written to a temporary file in the same directory, which then replaces
+    /// the file at the given path, so that the existing file is left intact if writing fails.
+    ///
+    /// As with every method that writes a sheet, the sheet's [`updated_at()`][updated_at] time is
+    /// set to the current time, unless writing fails.
+    ///
+    /// [updated_at]: #method.updated_at
+    pub fn write_to_path(&mut self, path: &Path) -> Result<(), SheetError> {
+        self.record_write(|sheet| sheet.store_at_path(path))
+    }
+
+    /// Write a sheet to the file at the given path, as with [`write_to_path()`][write_to_path], but
+    /// without changing its [`updated_at()`][updated_at] time.
+    ///
+    /// [write_to_path]: #method.write_to_path
+    /// [updated_at]: #method.updated_at
+    pub(crate) fn store_at_path(&self, path: &Path) -> Result<(), SheetError> {
+        write_atomically(path, |file| self.write_json(file))
+    }
+
+    /// Attempt to write a sheet to the file at the given path, as with
+    /// [`write_to_path()`][write_to_path], first backing up the existing file with
+    /// [`backup_file()`][backup_file] if there is one.
+    ///
+    /// Returns the path to the backup, if one was made.
+    ///
+    /// [write_to_path]: #method.write_to_path
+    /// [backup_file]: #method.backup_file
+    pub fn write_to_path_with_backup(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<PathBuf>, SheetError> {
+        let backup_path = match Self::backup_file(path) {
+            Ok(backup_path) => Some(backup_path),
+            Err(SheetError::BackupSheet(e)) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        self.write_to_path(path)?;
+
+        Ok(backup_path)
+    }
+
+    /// Attempt to write a sheet to the file at the default location, as determined by
+    /// [`default_loc()`][default], without blocking.
+    ///
+    /// As with [`write_default()`][write_default], the data directory is created first if it
+    /// doesn't exist yet. Unlike [`write_default()`][write_default], this doesn't lock the default
+    /// sheet.
+    ///
+    /// [default]: #method.default_loc
+    /// [write_default]: #method.write_default
+    #[cfg(feature = "tokio")]
+    pub async fn write_default_async(&mut self) -> Result<(), SheetError> {
+        tokio::fs::create_dir_all(Self::default_dir()?)
+            .await
+            .map_err(SheetError::CreateDataDir)?;
+
+        self.write_to_path_async(&Self::default_loc()?).await
+    }
+
+    /// Attempt to write a sheet to the file at the given path, without blocking.
+    ///
+    /// As with [`write_to_path()`][write_to_path], the existing file is left intact if writing
+    /// fails.
+    ///
+    /// [write_to_path]: #method.write_to_path
+    #[cfg(feature = "tokio")]
+    pub async fn write_to_path_async(&mut self, path: &Path) -> Result<(), SheetError> {
+        let previous = self.updated_at.replace(Utc::now());
+
+        let result = match serialize_json(self, false) {
+            Ok(sheet_json) => write_atomically_async(path, sheet_json.as_bytes()).await,
+            Err(e) => Err(e),
+        };
+
+        if result.is_err() {
+            self.updated_at = previous;
+        }
+
+        result
+    }
+
+    /// Attempt to write a sheet to the file at the default location, as determined by
+    /// [`default_loc()`][default], formatted as indented, multi-line JSON.
+    ///
+    /// As with [`write_default()`][write_default], the data directory is created first if it
+    /// doesn't exist yet.
+    ///
+    /// [default]: #method.default_loc
+    /// [write_default]: #method.write_default
+    pub fn write_default_pretty(&mut self) -> Result<(), SheetError> {
+        Self::ensure_data_dir()?;
+        let _lock = lock_default_dir(LockMode::Exclusive)?;
+        self.write_to_path_pretty(&Self::default_loc()?)
+    }
+
+    /// Attempt to write a sheet to the file at the given path, formatted as indented, multi-line
+    /// JSON.
+    ///
+    /// This is easier to read and to compare between versions than the compact output of
+    /// [`write_to_path()`][write_to_path], and can be loaded in the same way.
+    ///
+    /// [write_to_path]: #method.write_to_path
+    pub fn write_to_path_pretty(&mut self, path: &Path) -> Result<(), SheetError> {
+        self.record_write(|sheet| {
+            let sheet_json = serialize_json(sheet, true)?;
+
+            write_atomically(path, |file| {
+                write!(file, "{}", sheet_json).map_err(SheetError::WriteSheet)
+            })
+        })
+    }
+
+    /// Attempt to write a sheet to the TOML file at the given path.
+    #[cfg(feature = "toml")]
+    pub fn write_to_toml_path(&mut self, path: &Path) -> Result<(), SheetError> {
+        self.record_write(|sheet| {
+            let new_sheet_toml = toml::to_string(sheet).map_err(SheetError::SerializeTomlSheet)?;
+
+            write_atomically(path, |file| {
+                write!(file, "{}", new_sheet_toml).map_err(SheetError::WriteSheet)
+            })
+        })
+    }
+
+    /// Attempt to write a sheet to the file at the given path, in the format indicated by the
+    /// file's extension, as described in
+    /// [`from_file_detecting_format()`][from_file_detecting_format].
+    ///
+    /// [from_file_detecting_format]: #method.from_file_detecting_format
+    pub fn write_to_path_detecting_format(&mut self, path: &Path) -> Result<(), SheetError> {
+        match format_extension(path).as_str() {
+            "json" => self.write_to_path(path),
+            #[cfg(feature = "toml")]
+            "toml" => self.write_to_toml_path(path),
+            extension => Err(SheetError::UnknownFormat(extension.to_owned())),
+        }
+    }
+
+    /// Attempt to write a sheet to the given writer as CSV.
+    ///
+    /// The CSV data has a header row, followed by one row per event with the columns `start`,
+    /// `stop` (empty for an ongoing event), `project`, `description` and `tags` (separated by
+    /// semicolons).
+    #[cfg(feature = "csv")]
+    pub fn to_csv_writer<W>(&self, writer: W) -> Result<(), SheetError>
+    where
+        W: Write,
+    {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+
+        for event in &self.events {
+            csv_writer
+                .serialize(CsvEvent::from_event(event))
+                .map_err(SheetError::WriteCsv)?;
+        }
+
+        csv_writer
+            .flush()
+            .map_err(|e| SheetError::WriteCsv(e.into()))
+    }
+
+    /// Attempt to write a sheet to the given writer as CSV, in the format exported by
+    /// [Toggl][toggl], so that it can be imported into Toggl.
+    ///
+    /// Times are written in local time, to the nearest second. Ongoing events are written with an
+    /// empty end date, end time and duration.
+    ///
+    /// [toggl]: https://toggl.com
+    #[cfg(feature = "csv")]
+    pub fn to_toggl_csv_writer<W>(&self, writer: W) -> Result<(), SheetError>
+    where
+        W: Write,
+    {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+
+        for event in &self.events {
+            csv_writer
+                .serialize(TogglEvent::from_event(event))
+                .map_err(SheetError::WriteCsv)?;
+        }
+
+        csv_writer
+            .flush()
+            .map_err(|e| SheetError::WriteCsv(e.into()))
+    }
+
+    /// Attempt to write a sheet to the given writer as an [iCalendar][ical] file, which can be
+    /// imported into most calendar applications.
+    ///
+    /// Each event is written as a `VEVENT`, with the event's description (if it has one) as its
+    /// `SUMMARY`. An ongoing time-tracking period is written as ending at the current time.
+    ///
+    /// [ical]: https://tools.ietf.org/html/rfc5545
+    #[cfg(feature = "ical")]
+    pub fn to_ical_writer<W>(&self, mut writer: W) -> Result<(), SheetError>
+    where
+        W: Write,
+    {
+        use icalendar::{Calendar, Component, EventLike};
+
+        let now = Utc::now();
+        let mut calendar = Calendar::new();
+
+        for event in &self.events {
+            let stop = event.stop.unwrap_or(now);
+
+            let mut vevent = icalendar::Event::new();
+            vevent
+                .uid(&format!("{}@punch-clock", event.start.timestamp_millis()))
+                .starts(event.start)
+                .ends(stop);
+
+            if let Some(description) = &event.description {
+                vevent.summary(description);
+            }
+
+            calendar.push(vevent.done());
+        }
+
+        write!(&mut writer, "{}", calendar.done()).map_err(SheetError::WriteSheet)
+    }
+
+    /// Attempt to write a sheet to the given writer.
+    pub fn write_to_writer<W>(&mut self, writer: W) -> Result<(), SheetError>
+    where
+        W: Write,
+    {
+        self.record_write(|sheet| sheet.write_json(writer))
+    }
+
+    /// Write a sheet to the given writer as compact JSON, without changing its
+    /// [`updated_at()`][updated_at] time.
+    ///
+    /// [updated_at]: #method.updated_at
+    fn write_json<W>(&self, mut writer: W) -> Result<(), SheetError>
+    where
+        W: Write,
+    {
+        let sheet_json = serialize_json(self, false)?;
+
+        write!(&mut writer, "{}", sheet_json).map_err(SheetError::WriteSheet)
+    }
+
+    /// Serialize a sheet to a compact JSON string.
+    pub fn to_json_string(&self) -> String {
+        // Serializing to JSON only fails for maps with keys that aren't strings, or for types whose
+        // Serialize implementations return errors. The serialized fields of a sheet are only
+        // strings, times and booleans, possibly in options and vectors, none of which do either.
+        serialize_json(self, false).expect("sheets can always be serialized to JSON")
+    }
+
+    /// Serialize a sheet to an indented, multi-line JSON string.
+    pub fn to_json_string_pretty(&self) -> String {
+        // As in `to_json_string()`, serializing a sheet can't fail.
+        serialize_json(self, true).expect("sheets can always be serialized to JSON")
+    }
+
+    /// Attempt to serialize a sheet to a compact JSON string, with times written as whole numbers of
+    /// seconds since the Unix epoch rather than as strings.
+    ///
+    /// This makes the JSON smaller, but fractions of a second are lost. Sheets serialized like this
+    /// must be parsed with [`from_epoch_json()`][from_epoch_json].
+    ///
+    /// [from_epoch_json]: #method.from_epoch_json
+    pub fn to_epoch_json(&self) -> Result<String, SheetError> {
+        serialize_json(&EpochSheet::from_sheet(self), false)
+    }
+
+    /// Attempt to parse a sheet from a JSON string produced by
+    /// [`to_epoch_json()`][to_epoch_json].
+    ///
+    /// If the string is empty, an empty sheet is returned.
+    ///
+    /// [to_epoch_json]: #method.to_epoch_json
+    pub fn from_epoch_json(sheet_json: &str) -> Result<Sheet, SheetError> {
+        if sheet_json.is_empty() {
+            Ok(Sheet::default())
+        } else {
+            serde_json::from_str::<EpochSheet>(sheet_json)
+                .map(EpochSheet::into_sheet)
+                .map_err(SheetError::ParseSheet)
+        }
+    }
+
+    /// Format the sheet as a GitHub-flavored Markdown table, with one row per event showing its
+    /// date, start and stop times (in UTC), duration and description.
+    ///
+    /// The stop time and duration of an ongoing time-tracking period are shown as `—`.
+    pub fn to_markdown_table(&self) -> String {
+        use std::fmt::Write as _;
+
+        let rows: Vec<[String; 5]> = self
+            .table_rows()
+            .map(|row| row.map(|cell| cell.replace('|', "\\|").replace('\n', " ")))
+            .collect();
+
+        let mut widths = TABLE_HEADINGS.map(|heading| heading.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = std::cmp::max(*width, cell.chars().count());
+            }
+        }
+
+        let mut table = String::new();
+
+        let separator = widths.map(|width| "-".repeat(width));
+        for row in std::iter::once(TABLE_HEADINGS.map(str::to_owned))
+            .chain(std::iter::once(separator))
+            .chain(rows)
+        {
+            table.push('|');
+            for (cell, width) in row.iter().zip(widths) {
+                // Writing to a String can't fail.
+                let _ = write!(table, " {:<w$} |", cell, w = width);
+            }
+            table.push('\n');
+        }
+
+        table
+    }
+
+    /// Format the sheet as an HTML table, with one row per event and the same columns as
+    /// [`to_markdown_table()`][markdown].
+    ///
+    /// The table has no styling, so that it can be embedded in an email or web page and styled to
+    /// match.
+    ///
+    /// [markdown]: #method.to_markdown_table
+    pub fn to_html_table(&self) -> String {
+        let mut table = String::from("<table>\n<thead>\n<tr>");
+        for heading in TABLE_HEADINGS {
+            table.push_str(&format!("<th>{}</th>", heading));
+        }
+        table.push_str("</tr>\n</thead>\n<tbody>\n");
 
-        match File::create(&path) {
-            Ok(mut sheet_file) => {
-                write!(&mut sheet_file, "{}", new_sheet_json).map_err(SheetError::WriteSheet)
+        for row in self.table_rows() {
+            table.push_str("<tr>");
+            for cell in row {
+                table.push_str(&format!("<td>{}</td>", escape_html(&cell)));
             }
-            Err(e) => Err(SheetError::WriteSheet(e)),
+            table.push_str("</tr>\n");
         }
+
+        table.push_str("</tbody>\n</table>\n");
+        table
+    }
+
+    /// The cells of each row of a table of the events in this sheet, with the columns in
+    /// `TABLE_HEADINGS`.
+    fn table_rows(&self) -> impl Iterator<Item = [String; 5]> + '_ {
+        self.events.iter().map(|event| {
+            let (stop, duration) = match event.stop {
+                Some(stop) => (
+                    stop.format("%H:%M:%S").to_string(),
+                    format_duration(event.duration()),
+                ),
+                None => ("—".to_owned(), "—".to_owned()),
+            };
+
+            [
+                event.start.format("%Y-%m-%d").to_string(),
+                event.start.format("%H:%M:%S").to_string(),
+                stop,
+                duration,
+                event.description.clone().unwrap_or_default(),
+            ]
+        })
     }
 
     /// Record a punch-in (start of a time-tracking period) at the current time.
@@ -110,9 +764,66 @@ impl Sheet {
 
     /// Record a punch-in (start of a time-tracking period) at the given time.
     pub fn punch_in_at(&mut self, time: DateTime<Utc>) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_with(Event::new(time))
+    }
+
+    /// Record a punch-in (start of a time-tracking period) at the current time, with a description
+    /// of the work being done.
+    pub fn punch_in_with_description(
+        &mut self,
+        description: &str,
+    ) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_at_with_description(Utc::now(), description)
+    }
+
+    /// Record a punch-in (start of a time-tracking period) at the given time, with a description of
+    /// the work being done.
+    pub fn punch_in_at_with_description(
+        &mut self,
+        time: DateTime<Utc>,
+        description: &str,
+    ) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_with(Event::with_description(time, description))
+    }
+
+    /// Record a punch-in (start of a time-tracking period) at the current time, for the given
+    /// project.
+    pub fn punch_in_for_project(&mut self, project: &str) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_at_for_project(Utc::now(), project)
+    }
+
+    /// Record a punch-in (start of a time-tracking period) at the given time, for the given
+    /// project.
+    pub fn punch_in_at_for_project(
+        &mut self,
+        time: DateTime<Utc>,
+        project: &str,
+    ) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_with(Event::for_project(time, project))
+    }
+
+    /// Record a punch-in (start of a time-tracking period) at the current time, with the given
+    /// tags.
+    pub fn punch_in_with_tags(&mut self, tags: &[&str]) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_at_with_tags(Utc::now(), tags)
+    }
+
+    /// Record a punch-in (start of a time-tracking period) at the given time, with the given tags.
+    pub fn punch_in_at_with_tags(
+        &mut self,
+        time: DateTime<Utc>,
+        tags: &[&str],
+    ) -> Result<DateTime<Utc>, SheetError> {
+        self.punch_in_with(Event::with_tags(time, tags))
+    }
+
+    /// Record a punch-in by pushing the given (ongoing) event, as long as not already punched in.
+    fn punch_in_with(&mut self, event: Event) -> Result<DateTime<Utc>, SheetError> {
+        self.check_not_future(event.start)?;
+
         match self.events.last() {
             Some(Event { stop: Some(_), .. }) | None => {
-                let event = Event::new(time);
+                let time = event.start;
                 self.events.push(event);
                 Ok(time)
             }
@@ -128,84 +839,4485 @@ impl Sheet {
     }
 
     /// Record a punch-out (end of a time-tracking period) at the given time.
+    ///
+    /// The time must not be before the start of the ongoing time-tracking period, or before the
+    /// start of any other event in the sheet, which can only be the case if the sheet is out of
+    /// order.
     pub fn punch_out_at(&mut self, time: DateTime<Utc>) -> Result<DateTime<Utc>, SheetError> {
-        match self.events.last_mut() {
-            Some(ref mut event @ Event { stop: None, .. }) => {
-                event.stop = Some(time);
-                Ok(time)
-            }
+        self.check_not_future(time)?;
+
+        match self.events.last() {
+            Some(Event {
+                start, stop: None, ..
+            }) => check_range(*start, time)?,
             Some(Event {
                 stop: Some(stop_time),
                 ..
-            }) => Err(SheetError::PunchedOut(*stop_time)),
-            None => Err(SheetError::NoPunches),
+            }) => return Err(SheetError::PunchedOut(*stop_time)),
+            None => return Err(SheetError::NoPunches),
+        }
+
+        if self.events.iter().any(|e| e.start > time) {
+            return Err(SheetError::OutOfOrder(time));
+        }
+
+        if let Some(event) = self.events.last_mut() {
+            event.stop = Some(time);
+        }
+
+        Ok(time)
+    }
+
+    /// Record a punch-out and a punch-in at the current time, ending the ongoing time-tracking
+    /// period and immediately starting a new one, returning the times of the punch-out and the
+    /// punch-in.
+    pub fn punch_out_and_in(&mut self) -> Result<(DateTime<Utc>, DateTime<Utc>), SheetError> {
+        self.punch_out_and_in_at(Utc::now())
+    }
+
+    /// Record a punch-out and a punch-in at the given time, ending the ongoing time-tracking period
+    /// and immediately starting a new one, returning the times of the punch-out and the punch-in.
+    ///
+    /// Both punches are recorded at exactly the same instant, so there is no gap between the two
+    /// periods. The new period belongs to the same project, and has the same tags and billability,
+    /// as the one that ended.
+    pub fn punch_out_and_in_at(
+        &mut self,
+        time: DateTime<Utc>,
+    ) -> Result<(DateTime<Utc>, DateTime<Utc>), SheetError> {
+        let stop = self.punch_out_at(time)?;
+
+        let ended = self.events.last().ok_or(SheetError::NoPunches)?;
+        let start = self.punch_in_with(Event {
+            project: ended.project.clone(),
+            tags: ended.tags.clone(),
+            billable: ended.billable,
+            ..Event::new(time)
+        })?;
+
+        Ok((stop, start))
+    }
+
+    /// Create a new sheet containing the given events, which must be valid to append one after
+    /// another with [`push_event()`][push_event].
+    ///
+    /// This means the events must be in chronological order and must not overlap, and only the
+    /// last one can be ongoing.
+    ///
+    /// [push_event]: #method.push_event
+    pub fn from_events(events: Vec<Event>) -> Result<Sheet, SheetError> {
+        events
+            .into_iter()
+            .try_fold(Sheet::default(), Sheet::try_add)
+    }
+
+    /// Create a new sheet containing the given events, without checking that they are valid as
+    /// [`from_events()`][from_events] does.
+    ///
+    /// [from_events]: #method.from_events
+    pub fn from_events_unchecked(events: Vec<Event>) -> Sheet {
+        Sheet {
+            events,
+            ..Sheet::default()
+        }
+    }
+
+    /// Create a new empty sheet with the given name.
+    pub fn named(name: &str) -> Sheet {
+        Sheet {
+            name: Some(name.to_owned()),
+            ..Sheet::default()
+        }
+    }
+
+    /// Get the name of the sheet, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Set the name of the sheet.
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Get the description of the sheet, if it has one.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Set the description of the sheet.
+    pub fn set_description(&mut self, description: &str) -> &mut Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Get when the sheet was created, if known.
+    ///
+    /// This is only unknown for sheets created before it was recorded.
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// Get when the sheet was last written, if it has been.
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    /// Run the given write, with the sheet's [`updated_at()`][updated_at] time set to the current
+    /// time beforehand so that it is included in what's written, and restored if the write fails.
+    ///
+    /// [updated_at]: #method.updated_at
+    fn record_write<T, F>(&mut self, write: F) -> Result<T, SheetError>
+    where
+        F: FnOnce(&Sheet) -> Result<T, SheetError>,
+    {
+        let previous = self.updated_at.replace(Utc::now());
+
+        let result = write(self);
+        if result.is_err() {
+            self.updated_at = previous;
+        }
+
+        result
+    }
+
+    /// Get how far after the current time punch-ins and punch-outs are allowed to be recorded at.
+    ///
+    /// This is 60 seconds unless changed with [`set_future_tolerance()`][set].
+    ///
+    /// [set]: #method.set_future_tolerance
+    pub fn future_tolerance(&self) -> Duration {
+        self.future_tolerance
+            .unwrap_or_else(|| Duration::seconds(DEFAULT_FUTURE_TOLERANCE_SECS))
+    }
+
+    /// Set how far after the current time punch-ins and punch-outs are allowed to be recorded at.
+    ///
+    /// This setting is not saved when the sheet is written.
+    pub fn set_future_tolerance(&mut self, tolerance: Duration) -> &mut Self {
+        self.future_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Check that the given punch time is not too far in the future, according to
+    /// [`future_tolerance()`][tolerance].
+    ///
+    /// [tolerance]: #method.future_tolerance
+    fn check_not_future(&self, time: DateTime<Utc>) -> Result<(), SheetError> {
+        if time > Utc::now() + self.future_tolerance() {
+            Err(SheetError::FutureTime(time))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Change the start and/or stop times of the event at the given index.
+    ///
+    /// Only the times that are given are replaced; passing `Some(None)` as `new_stop` makes the
+    /// event ongoing again. The edited event must not stop before it starts.
+    pub fn edit_event(
+        &mut self,
+        index: usize,
+        new_start: Option<DateTime<Utc>>,
+        new_stop: Option<Option<DateTime<Utc>>>,
+    ) -> Result<(), SheetError> {
+        let event = self
+            .events
+            .get_mut(index)
+            .ok_or(SheetError::IndexOutOfBounds(index))?;
+
+        let start = new_start.unwrap_or(event.start);
+        let stop = new_stop.unwrap_or(event.stop);
+
+        if let Some(stop) = stop {
+            check_range(start, stop)?;
+        }
+
+        event.start = start;
+        event.stop = stop;
+
+        Ok(())
+    }
+
+    /// Change the start time of the most recent event, returning the new start time.
+    ///
+    /// If the most recent event has finished, the new start time must not be after its stop time.
+    pub fn amend_last_punch_in(
+        &mut self,
+        new_start: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, SheetError> {
+        let event = self.events.last_mut().ok_or(SheetError::NoPunches)?;
+
+        if let Some(stop) = event.stop {
+            check_range(new_start, stop)?;
+        }
+
+        event.start = new_start;
+        Ok(new_start)
+    }
+
+    /// Change the stop time of the most recent event, returning the new stop time.
+    ///
+    /// Unlike [`punch_out_at()`][punch_out_at], this only applies when already punched out, and
+    /// replaces the existing stop time. The new stop time must not be before the event's start time.
+    ///
+    /// [punch_out_at]: #method.punch_out_at
+    pub fn amend_last_punch_out(
+        &mut self,
+        new_stop: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, SheetError> {
+        match self.events.last_mut() {
+            Some(event @ Event { stop: Some(_), .. }) => {
+                check_range(event.start, new_stop)?;
+
+                event.stop = Some(new_stop);
+                Ok(new_stop)
+            }
+            Some(Event { start, .. }) => Err(SheetError::PunchedIn(*start)),
+            None => Err(SheetError::NoPunches),
+        }
+    }
+
+    /// Replace the completed event at the given index with two consecutive events, the first
+    /// stopping and the second starting at the given time.
+    ///
+    /// The split time must be strictly between the event's start and stop times. Both new events
+    /// keep the description, project and tags of the original event.
+    pub fn split_event_at(
+        &mut self,
+        index: usize,
+        split_time: DateTime<Utc>,
+    ) -> Result<(), SheetError> {
+        let event = self
+            .events
+            .get_mut(index)
+            .ok_or(SheetError::IndexOutOfBounds(index))?;
+
+        let stop = event.stop.ok_or(SheetError::OngoingEvent(index))?;
+
+        if split_time <= event.start || split_time >= stop {
+            return Err(SheetError::OutsideEvent(split_time));
+        }
+
+        let second = Event {
+            start: split_time,
+            ..event.clone()
+        };
+        event.stop = Some(split_time);
+
+        self.events.insert(index + 1, second);
+        Ok(())
+    }
+
+    /// Insert a completed event into the sheet, keeping the events in chronological order.
+    ///
+    /// The event must not stop before it starts, and must not overlap any existing event.
+    pub fn insert_event(&mut self, event: Event) -> Result<(), SheetError> {
+        let stop = event.stop.ok_or(SheetError::IncompleteEvent)?;
+        check_range(event.start, stop)?;
+
+        if let Some(index) = self.events.iter().position(|e| e.overlaps(&event)) {
+            return Err(SheetError::Overlapping(index));
+        }
+
+        let index = self.events.partition_point(|e| e.start < event.start);
+        self.events.insert(index, event);
+
+        Ok(())
+    }
+
+    /// Append an event to the end of the sheet.
+    ///
+    /// The event must not stop before it starts, and must not start before the last event in the
+    /// sheet stops. An event can't be appended after an ongoing time-tracking period.
+    pub fn push_event(&mut self, event: Event) -> Result<(), SheetError> {
+        if let Some(stop) = event.stop {
+            check_range(event.start, stop)?;
         }
-    }
 
-    /// Get the current status of time-tracking, including the time at which the status last
-    /// changed.
-    pub fn status(&self) -> SheetStatus {
         match self.events.last() {
+            Some(Event {
+                start, stop: None, ..
+            }) => return Err(SheetError::PunchedIn(*start)),
             Some(Event {
                 stop: Some(stop), ..
-            }) => SheetStatus::PunchedOut(*stop),
-            Some(Event { start, .. }) => SheetStatus::PunchedIn(*start),
-            None => SheetStatus::Empty,
+            }) if event.start < *stop => return Err(SheetError::OutOfOrder(event.start)),
+            _ => {}
         }
+
+        self.events.push(event);
+        Ok(())
     }
 
-    /// Count the amount of time for which there was recorded work between the two given instants,
-    /// including an ongoing time-tracking period if there is one.
-    pub fn count_range(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+    /// Append an event to the sheet, as [`push_event()`][push_event] does, returning the sheet.
+    ///
+    /// This is the non-panicking form of adding an event to a sheet with `+`.
+    ///
+    /// [push_event]: #method.push_event
+    pub fn try_add(mut self, event: Event) -> Result<Sheet, SheetError> {
+        self.push_event(event)?;
+        Ok(self)
+    }
+
+    /// Remove the event at the given index, returning it.
+    pub fn delete_event(&mut self, index: usize) -> Result<Event, SheetError> {
+        if index < self.events.len() {
+            Ok(self.events.remove(index))
+        } else {
+            Err(SheetError::IndexOutOfBounds(index))
+        }
+    }
+
+    /// Remove the most recent event, returning it.
+    pub fn delete_last_event(&mut self) -> Result<Event, SheetError> {
+        self.pop_last_event()
+    }
+
+    /// Remove the most recent event, whether it is ongoing or completed, returning it.
+    ///
+    /// This undoes the most recent punch-in, along with the punch-out that ended it if there was
+    /// one.
+    pub fn pop_last_event(&mut self) -> Result<Event, SheetError> {
+        self.events.pop().ok_or(SheetError::NoPunches)
+    }
+
+    /// Check whether any two events in the sheet overlap.
+    pub fn has_overlaps(&self) -> bool {
+        !self.find_overlaps().is_empty()
+    }
+
+    /// Find all pairs of events in the sheet that overlap, returned as pairs of indices with the
+    /// lower index first.
+    ///
+    /// Events that are merely adjacent (one stopping at the same instant the other starts) do not
+    /// overlap.
+    pub fn find_overlaps(&self) -> Vec<(usize, usize)> {
         self.events
             .iter()
-            .map(|e| (e.start, e.stop.unwrap_or_else(Utc::now)))
-            .filter(|(start, stop)| {
-                let entirely_before = start < &begin && stop < &begin;
-                let entirely_after = start > &end && stop > &end;
-
-                !(entirely_before || entirely_after)
+            .enumerate()
+            .flat_map(|(i, a)| {
+                self.events
+                    .iter()
+                    .enumerate()
+                    .skip(i + 1)
+                    .filter(move |(_, b)| a.overlaps(b))
+                    .map(move |(j, _)| (i, j))
             })
-            .map(|(start, stop)| {
-                let real_begin = std::cmp::max(begin, start);
-                let real_end = std::cmp::min(end, stop);
+            .collect()
+    }
+
+    /// Round the start and stop times of every completed event according to the given mode.
+    ///
+    /// Ongoing events are left unchanged.
+    pub fn apply_rounding(&mut self, mode: RoundingMode) {
+        for event in self.events.iter_mut().filter(|e| e.is_complete()) {
+            event.start = mode.round(event.start);
+            event.stop = event.stop.map(|stop| mode.round(stop));
+        }
+    }
 
-                real_end - real_begin
+    /// Get a copy of the sheet with the start and stop times of every completed event rounded
+    /// according to the given mode, as done by [`apply_rounding()`][apply_rounding].
+    ///
+    /// [apply_rounding]: #method.apply_rounding
+    pub fn with_rounding(&self, mode: RoundingMode) -> Sheet {
+        let mut rounded = self.clone();
+        rounded.apply_rounding(mode);
+        rounded
+    }
+
+    /// Get a new sheet containing only the parts of events that fall within the working hours of
+    /// the given schedule.
+    ///
+    /// Events are split into one event for each period of scheduled work they overlap, with any
+    /// time outside the schedule removed. If an ongoing event overlaps a period of scheduled work
+    /// that hasn't ended yet, the last part of it is left ongoing.
+    pub fn clamp_to_work_hours(&self, schedule: &WorkSchedule) -> Sheet {
+        let now = Utc::now();
+
+        self.events
+            .iter()
+            .flat_map(|e| {
+                schedule
+                    .windows_within(e.start, e.stop.unwrap_or(now))
+                    .into_iter()
+                    .map(move |(start, stop)| Event {
+                        start,
+                        stop: if e.is_ongoing() && stop == now {
+                            None
+                        } else {
+                            Some(stop)
+                        },
+                        ..e.clone()
+                    })
             })
+            .collect()
+    }
+
+    /// Count the amount of time for which work was scheduled by the given schedule during the
+    /// given period.
+    ///
+    /// Only scheduled time that has already passed is counted, so e.g. for [`Period::Week`][week]
+    /// this is the scheduled time from the start of the week until now. For [`Period::All`][all],
+    /// scheduled time is counted from the start of the earliest event.
+    ///
+    /// [week]: ../enum.Period.html#variant.Week
+    /// [all]: ../enum.Period.html#variant.All
+    pub fn scheduled_hours(&self, schedule: &WorkSchedule, period: &Period) -> Duration {
+        let (begin, end) = period.to_range();
+
+        let begin = match period {
+            Period::All => self.events.iter().map(|e| e.start).min().unwrap_or(end),
+            _ => begin,
+        };
+
+        schedule
+            .windows_within(begin, end)
+            .into_iter()
+            .map(|(begin, end)| end - begin)
             .fold(Duration::zero(), |acc, next| acc + next)
     }
-}
 
-/// Whether or not time is currently being tracked.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum SheetStatus {
-    /// Time is currently being tracked, and has been since the given instant.
-    PunchedIn(DateTime<Utc>),
-    /// Time is not currently being tracked, as of the given instant.
-    PunchedOut(DateTime<Utc>),
-    /// No time has ever been tracked.
-    Empty,
-}
+    /// Count how much more time was recorded during the given period than was scheduled by the
+    /// given schedule, as counted by [`scheduled_hours()`][scheduled_hours].
+    ///
+    /// A negative result means that less time was recorded than was scheduled.
+    ///
+    /// [scheduled_hours]: #method.scheduled_hours
+    pub fn overtime_duration(&self, schedule: &WorkSchedule, period: &Period) -> Duration {
+        self.count_period(period) - self.scheduled_hours(schedule, period)
+    }
 
-/// Errors arising through the use of [`Sheet`][sheet].
-///
-/// [sheet]: ./struct.Sheet.html
-#[derive(Error, Debug)]
-pub enum SheetError {
-    #[error("already punched in at {0}")]
-    PunchedIn(DateTime<Utc>),
-    #[error("not punched in, last punched out at {0}")]
-    PunchedOut(DateTime<Utc>),
-    #[error("not punched in, no punch-ins recorded")]
-    NoPunches,
-    #[error("unable to find sheet file")]
-    FindSheet,
-    #[error("unable to open sheet file")]
-    OpenSheet(#[source] std::io::Error),
-    #[error("unable to read sheet file")]
-    ReadSheet(#[source] std::io::Error),
-    #[error("unable to parse sheet")]
-    ParseSheet(#[source] serde_json::Error),
-    #[error("unable to write sheet to file")]
-    WriteSheet(#[source] std::io::Error),
+    /// Check the sheet against each of the given rules, returning every violation found.
+    pub fn validate(&self, rules: &[&dyn ValidationRule]) -> Vec<ValidationError> {
+        rules.iter().flat_map(|rule| rule.check(self)).collect()
+    }
+
+    /// Replace each group of overlapping events with a single event spanning from the earliest
+    /// start to the latest stop of the group, leaving the events sorted in chronological order.
+    ///
+    /// If any event in a group is ongoing, the merged event is ongoing too. The merged event keeps
+    /// the description, project and tags of the earliest event in its group. Afterwards,
+    /// [`has_overlaps()`][has_overlaps] always returns `false`.
+    ///
+    /// [has_overlaps]: #method.has_overlaps
+    pub fn merge_overlapping_events(&mut self) {
+        self.events.sort();
+
+        let mut merged: Vec<Event> = Vec::with_capacity(self.events.len());
+
+        for event in self.events.drain(..) {
+            match merged.last_mut() {
+                Some(last) if last.overlaps(&event) => {
+                    last.stop = match (last.stop, event.stop) {
+                        (Some(a), Some(b)) => Some(std::cmp::max(a, b)),
+                        _ => None,
+                    };
+                }
+                _ => merged.push(event),
+            }
+        }
+
+        self.events = merged;
+    }
+
+    /// Combine the events of this sheet and another into a new sheet, sorted in chronological
+    /// order.
+    ///
+    /// Events with the same start and stop times as an earlier event are considered duplicates and
+    /// removed, with events from this sheet taking precedence over those from `other`.
+    pub fn merge(&self, other: &Sheet) -> Sheet {
+        let mut merged = self.clone();
+        merged.merge_in_place(other);
+        merged
+    }
+
+    /// Add the events of another sheet into this one, sorting them in chronological order.
+    ///
+    /// Events with the same start and stop times as an earlier event are considered duplicates and
+    /// removed, with events already in this sheet taking precedence over those from `other`.
+    pub fn merge_in_place(&mut self, other: &Sheet) {
+        self.events.extend(other.events.iter().cloned());
+        self.dedup();
+    }
+
+    /// Fix common problems in a sheet that has been edited by hand, returning a summary of the
+    /// changes made.
+    ///
+    /// Events that stop before they start are either clipped to zero length, by moving their stop
+    /// time to their start time, or removed, depending on the given mode. The events are then
+    /// sorted with [`sort()`][sort], and duplicates are merged with [`dedup()`][dedup].
+    ///
+    /// Normalizing a sheet that has already been normalized makes no further changes.
+    ///
+    /// [sort]: #method.sort
+    /// [dedup]: #method.dedup
+    pub fn normalize(&mut self, mode: NormalizeMode) -> NormalizeReport {
+        let mut report = NormalizeReport::default();
+        let is_invalid = |e: &Event| e.stop.is_some_and(|stop| stop < e.start);
+
+        match mode {
+            NormalizeMode::ClipInvalid => {
+                for event in self.events.iter_mut().filter(|e| is_invalid(e)) {
+                    event.stop = Some(event.start);
+                    report.clipped += 1;
+                }
+            }
+            NormalizeMode::RemoveInvalid => {
+                let before = self.events.len();
+                self.events.retain(|e| !is_invalid(e));
+                report.removed = before - self.events.len();
+            }
+        }
+
+        let before = self.events.len();
+        self.dedup();
+        report.merged = before - self.events.len();
+
+        report
+    }
+
+    /// Sort the events in this sheet in chronological order, by start time and then by stop time.
+    ///
+    /// Recording punches with methods like [`punch_in_at()`][punch_in_at] and
+    /// [`punch_out_at()`][punch_out_at] doesn't sort the sheet, and neither does editing events
+    /// with [`edit_event()`][edit_event] or changing [`events`][events] directly, so this should be
+    /// called afterwards if the sheet may have become out of order.
+    ///
+    /// [punch_in_at]: #method.punch_in_at
+    /// [punch_out_at]: #method.punch_out_at
+    /// [edit_event]: #method.edit_event
+    /// [events]: #structfield.events
+    pub fn sort(&mut self) {
+        self.events.sort_by_key(|e| (e.start, e.stop));
+    }
+
+    /// Check whether the events in this sheet are in chronological order, as sorted by
+    /// [`sort()`][sort].
+    ///
+    /// [sort]: #method.sort
+    pub fn is_sorted(&self) -> bool {
+        self.events.is_sorted_by_key(|e| (e.start, e.stop))
+    }
+
+    /// Remove duplicate events from this sheet, sorting it by start time.
+    ///
+    /// Events with the same start and stop times as an earlier event are considered duplicates and
+    /// removed, so that only the first occurrence of each is kept.
+    pub fn dedup(&mut self) {
+        self.sort();
+        self.events
+            .dedup_by(|later, earlier| later.start == earlier.start && later.stop == earlier.stop);
+    }
+
+    /// Check whether this sheet has no duplicate events, as removed by [`dedup()`][dedup].
+    ///
+    /// [dedup]: #method.dedup
+    pub fn is_deduped(&self) -> bool {
+        let mut seen = BTreeSet::new();
+
+        self.events.iter().all(|e| seen.insert((e.start, e.stop)))
+    }
+
+    /// Get the number of events in the sheet.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check whether the sheet has no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Remove all events from the sheet.
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Remove all but the last `n` events from the sheet.
+    ///
+    /// If the sheet has `n` events or fewer, it is left unchanged.
+    pub fn truncate_to_last_n_events(&mut self, n: usize) {
+        let excess = self.events.len().saturating_sub(n);
+        self.events.drain(..excess);
+    }
+
+    /// Get the last `n` events in the sheet, or all of them if there are `n` or fewer.
+    pub fn last_n_events(&self, n: usize) -> &[Event] {
+        &self.events[self.events.len().saturating_sub(n)..]
+    }
+
+    /// Get the first event in the sheet, if there are any.
+    pub fn first_event(&self) -> Option<&Event> {
+        self.events.first()
+    }
+
+    /// Get the last event in the sheet, if there are any.
+    pub fn last_event(&self) -> Option<&Event> {
+        self.events.last()
+    }
+
+    /// Get the start time of the first event in the sheet, if there are any.
+    pub fn first_punch_time(&self) -> Option<DateTime<Utc>> {
+        self.first_event().map(|e| e.start)
+    }
+
+    /// Get the time of the most recent punch in the sheet: the stop time of the last event, or its
+    /// start time if it is ongoing.
+    pub fn last_punch_time(&self) -> Option<DateTime<Utc>> {
+        self.last_event().map(|e| e.stop.unwrap_or(e.start))
+    }
+
+    /// Get the ongoing time-tracking period, if currently punched in.
+    pub fn current_event(&self) -> Option<&Event> {
+        self.events.last().filter(|e| e.is_ongoing())
+    }
+
+    /// Get a mutable reference to the ongoing time-tracking period, if currently punched in.
+    pub fn current_event_mut(&mut self) -> Option<&mut Event> {
+        self.events.last_mut().filter(|e| e.is_ongoing())
+    }
+
+    /// Get the amount of time since punching in, if currently punched in.
+    pub fn time_since_punch_in(&self) -> Option<Duration> {
+        self.time_since_punch_in_at(Utc::now())
+    }
+
+    /// Get the amount of time between punching in and the given time, if currently punched in.
+    pub fn time_since_punch_in_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.current_event().map(|e| now - e.start)
+    }
+
+    /// Get the amount of time since last punching out, if currently punched out.
+    ///
+    /// Returns `None` if currently punched in, or if no time has been tracked yet.
+    pub fn time_since_last_activity(&self) -> Option<Duration> {
+        self.time_since_last_activity_at(Utc::now())
+    }
+
+    /// Get the amount of time between last punching out and the given time, if currently punched
+    /// out.
+    ///
+    /// Returns `None` if currently punched in, or if no time has been tracked yet.
+    pub fn time_since_last_activity_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.last_event()
+            .and_then(|e| e.stop)
+            .map(|stop| now - stop)
+    }
+
+    /// Get the first event in the sheet during which the given time falls, as determined by
+    /// [`Event::contains_time()`][contains_time].
+    ///
+    /// [contains_time]: ../struct.Event.html#method.contains_time
+    pub fn event_at_time(&self, time: DateTime<Utc>) -> Option<&Event> {
+        self.events.iter().find(|e| e.contains_time(time))
+    }
+
+    /// Get a summary of the sheet, with the figures most often needed to show the state of
+    /// time-tracking, all calculated as of the same instant.
+    pub fn summary(&self) -> SheetSummary {
+        let now = Utc::now();
+        let count_period = |period: Period| {
+            let (begin, end) = period.to_range_at(now);
+            count_events_in_range(&self.events, begin, end, now)
+        };
+
+        SheetSummary {
+            status: self.status(),
+            total_duration: self.total_duration_at(now),
+            session_count: self.session_count(),
+            today_duration: count_period(Period::Today),
+            this_week_duration: count_period(Period::Week),
+            as_of: now,
+        }
+    }
+
+    /// Get a report of the time tracked between the two given instants, with the figures most often
+    /// needed to describe a period of work.
+    ///
+    /// Events are clipped to the range as by [`clone_range()`][clone_range] before the figures are
+    /// calculated, so events that only partly overlap the range count only the part within it.
+    ///
+    /// [clone_range]: #method.clone_range
+    pub fn report(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> SheetReport {
+        let within = self.clone_range(begin, end);
+
+        let total_duration = within.total_duration();
+        let working_days = working_days(&within.events).len() as u32;
+
+        SheetReport {
+            total_duration,
+            session_count: within.session_count(),
+            working_days,
+            average_per_day: match working_days {
+                0 => Duration::zero(),
+                days => total_duration / days as i32,
+            },
+            longest_session: within.longest_session().map(Event::duration),
+            shortest_session: within.shortest_session().map(Event::duration),
+            gaps: within
+                .gaps()
+                .into_iter()
+                .map(|(begin, end)| end - begin)
+                .collect(),
+            total_break_time: within.total_break_time(),
+        }
+    }
+
+    /// Get the current status of time-tracking, including the time at which the status last
+    /// changed.
+    pub fn status(&self) -> SheetStatus {
+        match self.events.last() {
+            Some(Event {
+                stop: Some(stop), ..
+            }) => SheetStatus::PunchedOut(*stop),
+            Some(Event { start, .. }) => SheetStatus::PunchedIn(*start),
+            None => SheetStatus::Empty,
+        }
+    }
+
+    /// Count the amount of time for which there was recorded work between the two given instants,
+    /// including an ongoing time-tracking period if there is one.
+    ///
+    /// An ongoing time-tracking period counts as continuing until the current time, but only the
+    /// part of it before `end` is counted, so a range that ends in the past never includes time
+    /// that hasn't passed yet.
+    ///
+    /// If `end` is before `begin`, no time is counted and zero is returned. Use
+    /// [`count_range_checked()`][checked] to treat this as an error instead.
+    ///
+    /// [checked]: #method.count_range_checked
+    pub fn count_range(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+        count_events_in_range(&self.events, begin, end, Utc::now())
+    }
+
+    /// Count the amount of time for which there was recorded work between the two given instants,
+    /// as [`count_range()`][count_range] does, but without looking at events entirely outside the
+    /// range.
+    ///
+    /// This is faster than [`count_range()`][count_range] for large sheets, but the events must be
+    /// in chronological order and must not overlap, as they are when recorded by punching in and
+    /// out. Otherwise, the result is unspecified.
+    ///
+    /// [count_range]: #method.count_range
+    pub fn count_range_sorted(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+        let first = self
+            .events
+            .partition_point(|e| e.stop.is_some_and(|stop| stop < begin));
+
+        let within = self.events[first..].iter().take_while(|e| e.start <= end);
+
+        count_events_in_range(within, begin, end, Utc::now())
+    }
+
+    /// Count the amount of time for which there was recorded work between the two given instants,
+    /// as [`count_range()`][count_range] does, but fail with
+    /// [`SheetError::InvalidTimeRange`][invalid] if `end` is before `begin`.
+    ///
+    /// [count_range]: #method.count_range
+    /// [invalid]: ./enum.SheetError.html#variant.InvalidTimeRange
+    pub fn count_range_checked(
+        &self,
+        begin: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Duration, SheetError> {
+        check_range(begin, end)?;
+
+        Ok(self.count_range(begin, end))
+    }
+
+    /// Count the amount of time for which there was recorded work between the two given instants,
+    /// as [`count_range()`][count_range] does, formatted in the given format.
+    ///
+    /// [count_range]: #method.count_range
+    pub fn count_range_formatted(
+        &self,
+        begin: DateTime<Utc>,
+        end: DateTime<Utc>,
+        fmt: DurationFormat,
+    ) -> String {
+        fmt.format(self.count_range(begin, end))
+    }
+
+    /// Count the distinct days, in local time, on which at least one event started within the
+    /// range between the two given instants.
+    pub fn working_days_in_range(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> u32 {
+        working_days(
+            self.events
+                .iter()
+                .filter(|e| begin <= e.start && e.start <= end),
+        )
+        .len() as u32
+    }
+
+    /// Get the mean amount of time for which there was recorded work per working day between the
+    /// two given instants.
+    ///
+    /// This is the result of [`count_range()`][count_range] divided by the result of
+    /// [`working_days_in_range()`][working_days], or zero if there were no working days.
+    ///
+    /// [count_range]: #method.count_range
+    /// [working_days]: #method.working_days_in_range
+    pub fn average_daily_hours_in_range(
+        &self,
+        begin: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Duration {
+        match self.working_days_in_range(begin, end) {
+            0 => Duration::zero(),
+            days => self.count_range(begin, end) / days as i32,
+        }
+    }
+
+    /// Get all the events that overlap at least partially with the range between the two given
+    /// instants, including an ongoing time-tracking period if there is one.
+    pub fn events_in_range(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|e| overlaps_range(e.start, e.stop.unwrap_or_else(Utc::now), begin, end))
+            .collect()
+    }
+
+    /// Get all the events that lie entirely within the range between the two given instants,
+    /// including an ongoing time-tracking period if there is one.
+    pub fn events_strictly_within_range(
+        &self,
+        begin: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|e| begin <= e.start && e.stop.unwrap_or_else(Utc::now) <= end)
+            .collect()
+    }
+
+    /// Calculate the amount earned for all recorded time at the given rate, including an ongoing
+    /// time-tracking period if there is one.
+    ///
+    /// See [`Rate::earnings()`][earnings] for caveats about precision.
+    ///
+    /// [earnings]: ../billing/struct.Rate.html#method.earnings
+    pub fn total_earnings(&self, rate: &Rate) -> f64 {
+        rate.earnings(self.total_duration())
+    }
+
+    /// Calculate the amount earned at the given rate for the time recorded between the two given
+    /// instants, including an ongoing time-tracking period if there is one.
+    ///
+    /// See [`Rate::earnings()`][earnings] for caveats about precision.
+    ///
+    /// [earnings]: ../billing/struct.Rate.html#method.earnings
+    pub fn earnings_in_range(&self, begin: DateTime<Utc>, end: DateTime<Utc>, rate: &Rate) -> f64 {
+        rate.earnings(self.count_range(begin, end))
+    }
+
+    /// Calculate how much of the given goal has been met by the time recorded during its period.
+    pub fn progress_toward_goal(&self, goal: &Goal) -> GoalProgress {
+        GoalProgress::new(goal.target, self.count_period(&goal.period))
+    }
+
+    /// Estimate when the given goal will be met, assuming that from now on, `pace` worth of time
+    /// is recorded for every hour that passes.
+    ///
+    /// Returns `None` if the pace is zero or negative, or if the goal has already been met.
+    pub fn eta_for_goal(&self, goal: &Goal, pace: Duration) -> Option<DateTime<Utc>> {
+        let remaining = self.progress_toward_goal(goal).remaining;
+
+        if pace <= Duration::zero() || remaining <= Duration::zero() {
+            return None;
+        }
+
+        let hours_needed = remaining.num_milliseconds() as f64 / pace.num_milliseconds() as f64;
+        let wait = Duration::try_milliseconds((hours_needed * 3_600_000.0) as i64)?;
+
+        Utc::now().checked_add_signed(wait)
+    }
+
+    /// Get all the events that overlap at least partially with the given period, including an
+    /// ongoing time-tracking period if there is one.
+    pub fn events_in_period(&self, period: &Period) -> Vec<&Event> {
+        let (begin, end) = period.to_range();
+
+        self.events_in_range(begin, end)
+    }
+
+    /// Get a new sheet containing copies of all the events that overlap at least partially with
+    /// the given period, including an ongoing time-tracking period if there is one.
+    ///
+    /// Unlike [`clone_range()`][clone_range], events are not clipped to the period.
+    ///
+    /// [clone_range]: #method.clone_range
+    pub fn events_owned_in_period(&self, period: &Period) -> Sheet {
+        self.events_in_period(period).into_iter().cloned().collect()
+    }
+
+    /// Get a new sheet containing the parts of events that fall within the range between the two
+    /// given instants.
+    ///
+    /// Events that start before `begin` are clipped to start at `begin`, and events that stop (or
+    /// are still ongoing) after `end` are clipped to stop at `end`. An ongoing event is left
+    /// ongoing if `end` has not passed yet. Unlike [`events_in_range()`][events_in_range], this
+    /// returns owned events.
+    ///
+    /// [events_in_range]: #method.events_in_range
+    pub fn clone_range(&self, begin: DateTime<Utc>, end: DateTime<Utc>) -> Sheet {
+        let now = Utc::now();
+
+        self.events
+            .iter()
+            .filter(|e| e.start < end && e.stop.unwrap_or(now) > begin)
+            .map(|e| Event {
+                start: std::cmp::max(e.start, begin),
+                stop: match e.stop {
+                    Some(stop) => Some(std::cmp::min(stop, end)),
+                    None if end < now => Some(end),
+                    None => None,
+                },
+                ..e.clone()
+            })
+            .collect()
+    }
+
+    /// Remove all events that stopped before the given instant, returning them as a new sheet.
+    ///
+    /// Events that started before the cutoff but stopped after it (or are still ongoing) are kept
+    /// in this sheet intact, rather than being split.
+    pub fn archive_before(&mut self, cutoff: DateTime<Utc>) -> Sheet {
+        let (archived, kept): (Sheet, Sheet) = self
+            .events
+            .drain(..)
+            .partition(|e| e.stop.is_some_and(|stop| stop < cutoff));
+
+        self.events = kept.events;
+        archived
+    }
+
+    /// Divide the sheet into two new sheets: one containing the events that started before the
+    /// given instant, and one containing the events that started at or after it.
+    ///
+    /// Events in the first sheet that stop (or are still ongoing) after the cutoff are clipped to
+    /// stop at the cutoff, and the part of such an event after the cutoff is not included in
+    /// either sheet. If no event straddles the cutoff, no events are lost.
+    pub fn split_at(&self, cutoff: DateTime<Utc>) -> (Sheet, Sheet) {
+        let (before, after): (Vec<&Event>, Vec<&Event>) =
+            self.events.iter().partition(|e| e.start < cutoff);
+
+        let before = before
+            .into_iter()
+            .map(|e| Event {
+                stop: match e.stop {
+                    Some(stop) => Some(std::cmp::min(stop, cutoff)),
+                    None if cutoff < Utc::now() => Some(cutoff),
+                    None => None,
+                },
+                ..e.clone()
+            })
+            .collect();
+        let after = after.into_iter().cloned().collect();
+
+        (before, after)
+    }
+
+    /// Count the amount of time for which there was recorded work during the given period,
+    /// including an ongoing time-tracking period if there is one.
+    pub fn count_period(&self, period: &Period) -> Duration {
+        let (begin, end) = period.to_range();
+
+        self.count_range(begin, end)
+    }
+
+    /// Count the amount of time for which there was recorded work since midnight at the start of
+    /// the current day, in local time.
+    pub fn count_today(&self) -> Duration {
+        self.count_period(&Period::Today)
+    }
+
+    /// Count the amount of time for which there was recorded work since the start of the current
+    /// week, in local time.
+    ///
+    /// Weeks are ISO 8601 weeks, so they begin at midnight at the start of Monday.
+    pub fn count_this_week(&self) -> Duration {
+        self.count_period(&Period::Week)
+    }
+
+    /// Count the amount of time for which there was recorded work since midnight at the start of
+    /// the first day of the current month, in local time.
+    pub fn count_this_month(&self) -> Duration {
+        self.count_period(&Period::Month)
+    }
+
+    /// Count the amount of time for which there was recorded work since midnight at the start of
+    /// the 1st of January of the current year, in local time.
+    pub fn count_this_year(&self) -> Duration {
+        self.count_period(&Period::Year)
+    }
+
+    /// Count the total amount of time for which there was recorded work, including an ongoing
+    /// time-tracking period if there is one.
+    pub fn total_duration(&self) -> Duration {
+        self.total_duration_at(Utc::now())
+    }
+
+    /// Count the total amount of time for which there was recorded work, treating an ongoing
+    /// time-tracking period as continuing until the given time.
+    pub fn total_duration_at(&self, now: DateTime<Utc>) -> Duration {
+        sum_durations_at(&self.events, now)
+    }
+
+    /// Count the total amount of time for which there was recorded work, as
+    /// [`total_duration()`][total_duration] does, formatted in the given format.
+    ///
+    /// [total_duration]: #method.total_duration
+    pub fn total_duration_formatted(&self, fmt: DurationFormat) -> String {
+        fmt.format(self.total_duration())
+    }
+
+    /// Count the total amount of time for which there was recorded billable work, including an
+    /// ongoing time-tracking period if there is one.
+    pub fn billable_duration(&self) -> Duration {
+        self.billable_duration_at(Utc::now())
+    }
+
+    /// Count the total amount of time for which there was recorded billable work, treating an
+    /// ongoing time-tracking period as continuing until the given time.
+    pub fn billable_duration_at(&self, now: DateTime<Utc>) -> Duration {
+        sum_durations_at(self.billable_events(), now)
+    }
+
+    /// Count the total amount of time for which there was recorded non-billable work, including
+    /// an ongoing time-tracking period if there is one.
+    pub fn non_billable_duration(&self) -> Duration {
+        self.non_billable_duration_at(Utc::now())
+    }
+
+    /// Count the total amount of time for which there was recorded non-billable work, treating an
+    /// ongoing time-tracking period as continuing until the given time.
+    pub fn non_billable_duration_at(&self, now: DateTime<Utc>) -> Duration {
+        sum_durations_at(self.events.iter().filter(|e| !e.billable), now)
+    }
+
+    /// Iterate over all the billable events in the sheet.
+    pub fn billable_events(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(|e| e.billable)
+    }
+
+    /// Count the amount of time for which there was recorded work on each day, in local time,
+    /// including an ongoing time-tracking period if there is one.
+    ///
+    /// Events that span midnight are split, with each part counted towards the day it occurred on.
+    /// Days on which no work was recorded are not included.
+    pub fn daily_breakdown(&self) -> BTreeMap<NaiveDate, Duration> {
+        self.breakdown_by(|day| (day, day.succ_opt().unwrap()))
+    }
+
+    /// Count the amount of time for which there was recorded work in each ISO 8601 week, in local
+    /// time, including an ongoing time-tracking period if there is one.
+    ///
+    /// Weeks are keyed by the date of the Monday they begin on. Events that span the midnight
+    /// between two weeks are split, with each part counted towards the week it occurred in. Weeks
+    /// in which no work was recorded are not included.
+    pub fn weekly_breakdown(&self) -> BTreeMap<NaiveDate, Duration> {
+        self.breakdown_by(|day| {
+            let monday = day - Duration::days(day.weekday().num_days_from_monday().into());
+            (monday, monday + Duration::days(7))
+        })
+    }
+
+    /// Count the amount of time for which there was recorded work in each calendar month, in local
+    /// time, including an ongoing time-tracking period if there is one.
+    ///
+    /// Months are keyed by `(year, month)`, where `month` starts from 1 for January. Events that
+    /// span the midnight between two months are split, with each part counted towards the month it
+    /// occurred in. Months in which no work was recorded are not included.
+    pub fn monthly_breakdown(&self) -> BTreeMap<(i32, u32), Duration> {
+        self.breakdown_by(|day| {
+            let next_first = if day.month() == 12 {
+                NaiveDate::from_ymd_opt(day.year() + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(day.year(), day.month() + 1, 1)
+            };
+
+            ((day.year(), day.month()), next_first.unwrap())
+        })
+    }
+
+    /// Count the amount of time for which there was recorded work in each calendar year, in local
+    /// time, including an ongoing time-tracking period if there is one.
+    ///
+    /// Events that span the midnight between two years are split, with each part counted towards
+    /// the year it occurred in. Years in which no work was recorded are not included.
+    pub fn yearly_breakdown(&self) -> BTreeMap<i32, Duration> {
+        self.breakdown_by(|day| {
+            let next_first = NaiveDate::from_ymd_opt(day.year() + 1, 1, 1);
+
+            (day.year(), next_first.unwrap())
+        })
+    }
+
+    /// Group the events in the sheet by the day they started on, in local time.
+    ///
+    /// Events that span midnight are only included in the day they started on.
+    pub fn group_by_day(&self) -> BTreeMap<NaiveDate, Vec<&Event>> {
+        self.group_by(|e| e.start.with_timezone(&Local).date_naive())
+    }
+
+    /// Group the events in the sheet by the day they started on, in UTC.
+    ///
+    /// Events that span midnight are only included in the day they started on.
+    pub fn group_by_day_utc(&self) -> BTreeMap<NaiveDate, Vec<&Event>> {
+        self.group_by(|e| e.start.date_naive())
+    }
+
+    /// Group the events in the sheet by the key returned for each one by the given function.
+    fn group_by<K, F>(&self, key: F) -> BTreeMap<K, Vec<&Event>>
+    where
+        K: Ord,
+        F: Fn(&Event) -> K,
+    {
+        let mut groups: BTreeMap<K, Vec<&Event>> = BTreeMap::new();
+
+        for event in &self.events {
+            groups.entry(key(event)).or_default().push(event);
+        }
+
+        groups
+    }
+
+    /// Count the consecutive days, in local time, up to and including today on which at least one
+    /// event (complete or ongoing) started.
+    ///
+    /// If no event has started today yet, the streak up to and including yesterday is counted
+    /// instead, so that it doesn't reset before the day's work has begun.
+    pub fn streak(&self) -> u32 {
+        let days = working_days(&self.events);
+        let today = Local::now().date_naive();
+
+        let mut day = if days.contains(&today) {
+            today
+        } else {
+            today.pred_opt().unwrap()
+        };
+
+        let mut streak = 0;
+        while days.contains(&day) {
+            streak += 1;
+            day = day.pred_opt().unwrap();
+        }
+
+        streak
+    }
+
+    /// Count the most consecutive days, in local time, on which at least one event (complete or
+    /// ongoing) started, over the whole history of the sheet.
+    pub fn longest_streak(&self) -> u32 {
+        let mut longest = 0;
+        let mut current = 0;
+        let mut previous: Option<NaiveDate> = None;
+
+        for day in working_days(&self.events) {
+            current = match previous {
+                Some(previous) if previous.succ_opt() == Some(day) => current + 1,
+                _ => 1,
+            };
+            longest = std::cmp::max(longest, current);
+            previous = Some(day);
+        }
+
+        longest
+    }
+
+    /// Get the day, in local time, on which the most time was recorded, as counted by
+    /// [`daily_breakdown()`][daily_breakdown].
+    ///
+    /// If several days are tied, the earliest of them is returned.
+    ///
+    /// [daily_breakdown]: #method.daily_breakdown
+    pub fn busiest_day(&self) -> Option<NaiveDate> {
+        self.daily_breakdown()
+            .into_iter()
+            .max_by(|(a_day, a_total), (b_day, b_total)| {
+                a_total.cmp(b_total).then_with(|| b_day.cmp(a_day))
+            })
+            .map(|(day, _)| day)
+    }
+
+    /// Get the day, in local time, on which the least time was recorded, out of the days on which
+    /// any time was recorded at all, as counted by [`daily_breakdown()`][daily_breakdown].
+    ///
+    /// If several days are tied, the earliest of them is returned.
+    ///
+    /// [daily_breakdown]: #method.daily_breakdown
+    pub fn least_productive_day(&self) -> Option<NaiveDate> {
+        self.daily_breakdown()
+            .into_iter()
+            .min_by_key(|(_, total)| *total)
+            .map(|(day, _)| day)
+    }
+
+    /// Count the amount of time for which there was recorded work in each of a series of
+    /// contiguous buckets of local calendar days.
+    ///
+    /// `bucket` is given a local date, and must return the key of the bucket containing that date,
+    /// together with the first date of the following bucket. Events are split at the local
+    /// midnight at the start of that following date.
+    fn breakdown_by<K, F>(&self, bucket: F) -> BTreeMap<K, Duration>
+    where
+        K: Ord,
+        F: Fn(NaiveDate) -> (K, NaiveDate),
+    {
+        let mut breakdown = BTreeMap::new();
+
+        for event in &self.events {
+            let stop = event.stop.unwrap_or_else(Utc::now);
+            let mut start = event.start;
+
+            while start < stop {
+                let (key, next_first) = bucket(start.with_timezone(&Local).date_naive());
+                let next = std::cmp::min(stop, local_midnight(next_first));
+
+                *breakdown.entry(key).or_insert_with(Duration::zero) += next - start;
+                start = next;
+            }
+        }
+
+        breakdown
+    }
+
+    /// Get the periods of time between the end of each completed event and the start of the next
+    /// event, as pairs of the instants at which each period begins and ends.
+    ///
+    /// Events are taken in the order they appear in the sheet. Events that are adjacent or overlap
+    /// have no period of time between them, so they don't produce a gap.
+    pub fn gaps(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        self.events
+            .windows(2)
+            .filter_map(|pair| {
+                let stop = pair[0].stop?;
+                let next_start = pair[1].start;
+
+                if stop < next_start {
+                    Some((stop, next_start))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Count the total amount of time between events, as returned by [`gaps()`][gaps].
+    ///
+    /// [gaps]: #method.gaps
+    pub fn total_break_time(&self) -> Duration {
+        self.gaps()
+            .into_iter()
+            .map(|(begin, end)| end - begin)
+            .fold(Duration::zero(), |acc, next| acc + next)
+    }
+
+    /// Get the mean length of all sessions (completed events), or `None` if there are no sessions.
+    ///
+    /// An ongoing time-tracking period is not counted as a session.
+    pub fn average_session_duration(&self) -> Option<Duration> {
+        let (total, count) = self
+            .sessions()
+            .fold((Duration::zero(), 0), |(total, count), next| {
+                (total + next.duration(), count + 1)
+            });
+
+        if count == 0 {
+            None
+        } else {
+            Some(total / count)
+        }
+    }
+
+    /// Get the median length of all sessions (completed events), or `None` if there are no
+    /// sessions.
+    ///
+    /// An ongoing time-tracking period is not counted as a session. If there is an even number of
+    /// sessions, the lower of the two middle lengths is returned.
+    pub fn median_session_duration(&self) -> Option<Duration> {
+        let mut durations: Vec<Duration> = self.sessions().map(Event::duration).collect();
+        durations.sort_unstable();
+
+        if durations.is_empty() {
+            None
+        } else {
+            Some(durations[(durations.len() - 1) / 2])
+        }
+    }
+
+    /// Get the longest session (completed event), or `None` if there are no sessions.
+    ///
+    /// An ongoing time-tracking period is not counted as a session. If several sessions are equally
+    /// long, the last of them is returned.
+    pub fn longest_session(&self) -> Option<&Event> {
+        self.sessions().max_by_key(|e| e.duration())
+    }
+
+    /// Get the shortest session (completed event), or `None` if there are no sessions.
+    ///
+    /// An ongoing time-tracking period is not counted as a session. If several sessions are equally
+    /// short, the first of them is returned.
+    pub fn shortest_session(&self) -> Option<&Event> {
+        self.sessions().min_by_key(|e| e.duration())
+    }
+
+    /// Get the number of events in the sheet, including an ongoing time-tracking period if there is
+    /// one.
+    ///
+    /// This is always equal to the sum of [`completed_session_count()`][completed] and
+    /// [`ongoing_session_count()`][ongoing].
+    ///
+    /// [completed]: #method.completed_session_count
+    /// [ongoing]: #method.ongoing_session_count
+    pub fn session_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Get the number of completed events in the sheet.
+    pub fn completed_session_count(&self) -> usize {
+        self.sessions().count()
+    }
+
+    /// Get the number of ongoing events in the sheet.
+    pub fn ongoing_session_count(&self) -> usize {
+        self.events.iter().filter(|e| e.is_ongoing()).count()
+    }
+
+    /// Iterate over all sessions (completed events) in the sheet.
+    fn sessions(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(|e| e.is_complete())
+    }
+
+    /// Call the given function on each event in the sheet in turn, allowing it to modify them.
+    ///
+    /// After changing event times like this, the events may no longer be in chronological order,
+    /// so [`sort()`][sort] may need to be called afterwards. To move every event by the same
+    /// offset, use [`shift_all_by()`][shift_all_by] instead, which also checks the new times.
+    ///
+    /// [sort]: #method.sort
+    /// [shift_all_by]: #method.shift_all_by
+    pub fn apply<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut Event),
+    {
+        self.events.iter_mut().for_each(f);
+    }
+
+    /// Move every event in the sheet by the given offset, which may be negative, e.g. to correct
+    /// events imported with the wrong timezone.
+    ///
+    /// Fails if any event stops before it starts, or if moving an event would take its start or
+    /// stop time outside of the range of representable times, in which case no events are moved.
+    pub fn shift_all_by(&mut self, offset: Duration) -> Result<(), SheetError> {
+        let shift = |time: DateTime<Utc>| {
+            time.checked_add_signed(offset)
+                .ok_or(SheetError::OutOfRange(time))
+        };
+
+        let shifted = self
+            .events
+            .iter()
+            .map(|event| {
+                let start = shift(event.start)?;
+                let stop = event.stop.map(shift).transpose()?;
+
+                if let Some(stop) = stop {
+                    check_range(start, stop)?;
+                }
+
+                Ok((start, stop))
+            })
+            .collect::<Result<Vec<_>, SheetError>>()?;
+
+        for (event, (start, stop)) in self.events.iter_mut().zip(shifted) {
+            event.start = start;
+            event.stop = stop;
+        }
+
+        Ok(())
+    }
+
+    /// Keep only the events in this sheet for which the given predicate returns `true`, removing
+    /// the rest in place.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Event) -> bool,
+    {
+        self.events.retain(f);
+    }
+
+    /// Remove the events in this sheet for which the given predicate returns `true`, returning
+    /// them as a new sheet.
+    ///
+    /// The events left in this sheet and those in the returned sheet both keep their original
+    /// order.
+    pub fn extract_if<F>(&mut self, mut f: F) -> Sheet
+    where
+        F: FnMut(&Event) -> bool,
+    {
+        self.events.extract_if(.., |e| f(e)).collect()
+    }
+
+    /// Get a new sheet containing only the events belonging to the given project.
+    pub fn filter_by_project(&self, project: &str) -> Sheet {
+        self.events
+            .iter()
+            .filter(|e| e.project.as_deref() == Some(project))
+            .cloned()
+            .collect()
+    }
+
+    /// Partition the events in the sheet into new sheets by the project they belong to.
+    ///
+    /// Events that don't belong to any project are collected under `None`.
+    pub fn group_by_project(&self) -> HashMap<Option<String>, Sheet> {
+        let mut groups: HashMap<Option<String>, Sheet> = HashMap::new();
+
+        for event in &self.events {
+            groups
+                .entry(event.project.clone())
+                .or_default()
+                .events
+                .push(event.clone());
+        }
+
+        groups
+    }
+
+    /// Get a new sheet containing only the events that have the given tag.
+    pub fn filter_by_tag(&self, tag: &str) -> Sheet {
+        self.filter_by_all_tags(&[tag])
+    }
+
+    /// Get a new sheet containing only the events that have every one of the given tags.
+    pub fn filter_by_all_tags(&self, tags: &[&str]) -> Sheet {
+        self.events
+            .iter()
+            .filter(|e| tags.iter().all(|tag| e.has_tag(tag)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// A single row of a sheet in CSV format.
+#[cfg(feature = "csv")]
+#[derive(Serialize, Deserialize)]
+struct CsvEvent {
+    start: DateTime<Utc>,
+    #[serde(default)]
+    stop: Option<DateTime<Utc>>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tags: String,
+}
+
+#[cfg(feature = "csv")]
+impl CsvEvent {
+    fn from_event(event: &Event) -> Self {
+        CsvEvent {
+            start: event.start,
+            stop: event.stop,
+            project: event.project.clone(),
+            description: event.description.clone(),
+            tags: event.tags.join(";"),
+        }
+    }
+
+    fn into_event(self) -> Event {
+        Event {
+            start: self.start,
+            stop: self.stop,
+            description: self.description,
+            project: self.project,
+            tags: self
+                .tags
+                .split(';')
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            ..Event::new(self.start)
+        }
+    }
+}
+
+/// A sheet with its times serialized as seconds since the Unix epoch.
+#[derive(Serialize, Deserialize)]
+struct EpochSheet {
+    events: Vec<EpochEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl EpochSheet {
+    fn from_sheet(sheet: &Sheet) -> Self {
+        EpochSheet {
+            events: sheet.events.iter().map(EpochEvent::from_event).collect(),
+            name: sheet.name.clone(),
+            description: sheet.description.clone(),
+            created_at: sheet.created_at,
+            updated_at: sheet.updated_at,
+        }
+    }
+
+    fn into_sheet(self) -> Sheet {
+        Sheet {
+            events: self
+                .events
+                .into_iter()
+                .map(EpochEvent::into_event)
+                .collect(),
+            name: self.name,
+            description: self.description,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            future_tolerance: None,
+        }
+    }
+}
+
+/// An event with its times serialized as seconds since the Unix epoch.
+#[derive(Serialize, Deserialize)]
+struct EpochEvent {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    start: DateTime<Utc>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    stop: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(
+        default = "crate::event::default_billable",
+        skip_serializing_if = "crate::event::is_default_billable"
+    )]
+    billable: bool,
+}
+
+impl EpochEvent {
+    fn from_event(event: &Event) -> Self {
+        EpochEvent {
+            start: event.start,
+            stop: event.stop,
+            description: event.description.clone(),
+            project: event.project.clone(),
+            tags: event.tags.clone(),
+            billable: event.billable,
+        }
+    }
+
+    fn into_event(self) -> Event {
+        Event {
+            start: self.start,
+            stop: self.stop,
+            description: self.description,
+            project: self.project,
+            tags: self.tags,
+            billable: self.billable,
+        }
+    }
+}
+
+impl Default for Sheet {
+    /// Create a new empty sheet, created at the current time.
+    fn default() -> Self {
+        Sheet {
+            events: Vec::new(),
+            name: None,
+            description: None,
+            created_at: Some(Utc::now()),
+            updated_at: None,
+            future_tolerance: None,
+        }
+    }
+}
+
+impl Sheet {
+    /// The parts of a sheet that it's compared, ordered and hashed by.
+    fn cmp_key(&self) -> (&[Event], &Option<String>, &Option<String>) {
+        (&self.events, &self.name, &self.description)
+    }
+}
+
+impl PartialEq for Sheet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp_key() == other.cmp_key()
+    }
+}
+
+impl Eq for Sheet {}
+
+impl Hash for Sheet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cmp_key().hash(state);
+    }
+}
+
+impl PartialOrd for Sheet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Sheet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_key().cmp(&other.cmp_key())
+    }
+}
+
+impl Display for Sheet {
+    /// Format the sheet as a table with one row per event, showing its index, date, start and stop
+    /// times (in UTC) and duration, followed by the total duration of all events.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let now = Utc::now();
+        let durations: Vec<String> = self
+            .events
+            .iter()
+            .map(|event| format_duration(event.duration_at(now)))
+            .collect();
+        let total = format_duration(self.total_duration_at(now));
+
+        let index_width = std::cmp::max(1, self.events.len().saturating_sub(1).to_string().len());
+        let duration_width = durations
+            .iter()
+            .chain(std::iter::once(&total))
+            .map(String::len)
+            .fold("Duration".len(), std::cmp::max);
+
+        writeln!(
+            f,
+            "{:>iw$}  {:<10}  {:<8}  {:<8}  {:>dw$}",
+            "#",
+            "Date",
+            "Start",
+            "Stop",
+            "Duration",
+            iw = index_width,
+            dw = duration_width,
+        )?;
+
+        for (index, (event, duration)) in self.events.iter().zip(durations).enumerate() {
+            let stop = event.stop.map_or_else(
+                || "ongoing".to_owned(),
+                |stop| stop.format("%H:%M:%S").to_string(),
+            );
+
+            writeln!(
+                f,
+                "{:>iw$}  {:<10}  {:<8}  {:<8}  {:>dw$}",
+                index,
+                event.start.format("%Y-%m-%d"),
+                event.start.format("%H:%M:%S"),
+                stop,
+                duration,
+                iw = index_width,
+                dw = duration_width,
+            )?;
+        }
+
+        write!(
+            f,
+            "{:>lw$}  {:>dw$}",
+            "Total",
+            total,
+            lw = index_width + 32,
+            dw = duration_width,
+        )
+    }
+}
+
+impl IntoIterator for Sheet {
+    type Item = Event;
+    type IntoIter = std::vec::IntoIter<Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Sheet {
+    type Item = &'a Event;
+    type IntoIter = std::slice::Iter<'a, Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter()
+    }
+}
+
+impl FromIterator<Event> for Sheet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        Sheet {
+            events: iter.into_iter().collect(),
+            ..Sheet::default()
+        }
+    }
+}
+
+impl Extend<Event> for Sheet {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        self.events.extend(iter)
+    }
+}
+
+impl Add<Event> for Sheet {
+    type Output = Sheet;
+
+    /// Append an event to the sheet, as [`Sheet::push_event()`][push_event] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event can't be appended. Use [`Sheet::try_add()`][try_add] to handle this
+    /// instead.
+    ///
+    /// [push_event]: ./struct.Sheet.html#method.push_event
+    /// [try_add]: ./struct.Sheet.html#method.try_add
+    fn add(self, event: Event) -> Sheet {
+        self.try_add(event)
+            .unwrap_or_else(|e| panic!("unable to add event to sheet: {}", e))
+    }
+}
+
+impl Add<Sheet> for Sheet {
+    type Output = Sheet;
+
+    /// Combine the events of two sheets, as [`Sheet::merge()`][merge] does.
+    ///
+    /// The combined sheet keeps the metadata, such as the name and creation time, of the left-hand
+    /// sheet.
+    ///
+    /// [merge]: ./struct.Sheet.html#method.merge
+    fn add(mut self, other: Sheet) -> Sheet {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign<Sheet> for Sheet {
+    /// Add the events of another sheet into this one, as
+    /// [`Sheet::merge_in_place()`][merge_in_place] does.
+    ///
+    /// [merge_in_place]: ./struct.Sheet.html#method.merge_in_place
+    fn add_assign(&mut self, other: Sheet) {
+        self.merge_in_place(&other);
+    }
+}
+
+impl AddAssign<Event> for Sheet {
+    /// Append an event to the sheet, as [`Sheet::push_event()`][push_event] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event can't be appended. Use [`Sheet::push_event()`][push_event] to handle
+    /// this instead.
+    ///
+    /// [push_event]: ./struct.Sheet.html#method.push_event
+    fn add_assign(&mut self, event: Event) {
+        self.push_event(event)
+            .unwrap_or_else(|e| panic!("unable to add event to sheet: {}", e))
+    }
+}
+
+/// Write to the file at the given path using the given function, by first writing to a temporary
+/// file in the same directory which then replaces the file at the given path.
+///
+/// This means that the existing file is left intact if writing fails.
+fn write_atomically<F>(path: &Path, write: F) -> Result<(), SheetError>
+where
+    F: FnOnce(&mut File) -> Result<(), SheetError>,
+{
+    let temp_path = temp_path_for(path);
+
+    let written = File::create(&temp_path)
+        .map_err(SheetError::CreateTempFile)
+        .and_then(|mut temp_file| {
+            write(&mut temp_file)?;
+            temp_file.sync_all().map_err(SheetError::WriteSheet)
+        })
+        .and_then(|_| fs::rename(&temp_path, path).map_err(SheetError::RenameTempFile));
+
+    if written.is_err() {
+        // Don't leave a partially written temporary file lying around; if this fails too, the
+        // original error is more useful to the caller.
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    written
+}
+
+/// Write the given data to the file at the given path asynchronously, by first writing to a
+/// temporary file in the same directory which then replaces the file at the given path, as in
+/// [`write_atomically()`][write_atomically].
+///
+/// [write_atomically]: ./fn.write_atomically.html
+#[cfg(feature = "tokio")]
+async fn write_atomically_async(path: &Path, data: &[u8]) -> Result<(), SheetError> {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_path = temp_path_for(path);
+
+    let written = async {
+        let mut temp_file = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(SheetError::CreateTempFile)?;
+        temp_file
+            .write_all(data)
+            .await
+            .map_err(SheetError::WriteSheet)?;
+        temp_file.sync_all().await.map_err(SheetError::WriteSheet)?;
+
+        tokio::fs::rename(&temp_path, path)
+            .await
+            .map_err(SheetError::RenameTempFile)
+    }
+    .await;
+
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+    }
+
+    written
+}
+
+/// The path to the temporary file used while writing to the file at the given path.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut temp_name = OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_else(|| OsStr::new("sheet")));
+    temp_name.push(".tmp");
+
+    path.with_file_name(temp_name)
+}
+
+/// Ways of locking the directory containing the default sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LockMode {
+    /// Wait for a lock shared with other readers.
+    Shared,
+    /// Take a lock shared with other readers, failing if it can't be taken immediately.
+    TryShared,
+    /// Wait for a lock held by no other process.
+    Exclusive,
+}
+
+/// Lock the directory returned from [`Sheet::default_dir()`][default_dir], returning the lock
+/// file, which holds the lock until it is dropped.
+///
+/// The lock is taken on a separate lock file rather than the sheet file itself, because writing
+/// the sheet replaces its file with a new one. If the directory doesn't exist, there is no sheet
+/// to protect, so no lock is taken.
+///
+/// [default_dir]: ./struct.Sheet.html#method.default_dir
+fn lock_default_dir(mode: LockMode) -> Result<Option<File>, SheetError> {
+    let mut lock_path = Sheet::default_dir()?;
+    lock_path.push("sheet.lock");
+
+    let lock_file = match fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+    {
+        Ok(lock_file) => lock_file,
+        Err(e) if e.kind() == ErrorKind::NotFound && mode != LockMode::Exclusive => {
+            return Ok(None)
+        }
+        Err(e) => return Err(SheetError::LockSheet(e)),
+    };
+
+    match mode {
+        LockMode::Shared => FileExt::lock_shared(&lock_file).map_err(SheetError::LockSheet)?,
+        LockMode::TryShared => FileExt::try_lock_shared(&lock_file).map_err(|e| {
+            if e.raw_os_error() == fs2::lock_contended_error().raw_os_error() {
+                SheetError::SheetLocked
+            } else {
+                SheetError::LockSheet(e)
+            }
+        })?,
+        LockMode::Exclusive => {
+            FileExt::lock_exclusive(&lock_file).map_err(SheetError::LockSheet)?
+        }
+    }
+
+    Ok(Some(lock_file))
+}
+
+/// The lowercase extension of the file at the given path, or an empty string if it has none.
+fn format_extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Get the set of days, in local time, on which at least one of the given events started.
+fn working_days<'a, I>(events: I) -> BTreeSet<NaiveDate>
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    events
+        .into_iter()
+        .map(|e| e.start.with_timezone(&Local).date_naive())
+        .collect()
+}
+
+/// A single row of a sheet in Toggl's CSV format.
+#[cfg(feature = "csv")]
+#[derive(Serialize, Deserialize)]
+struct TogglEvent {
+    #[serde(rename = "User", default)]
+    user: String,
+    #[serde(rename = "Email", default)]
+    email: String,
+    #[serde(rename = "Client", default)]
+    client: String,
+    #[serde(rename = "Project", default)]
+    project: Option<String>,
+    #[serde(rename = "Task", default)]
+    task: String,
+    #[serde(rename = "Description", default)]
+    description: Option<String>,
+    #[serde(rename = "Billable", default)]
+    billable: Option<String>,
+    #[serde(rename = "Start date")]
+    start_date: NaiveDate,
+    #[serde(rename = "Start time")]
+    start_time: chrono::NaiveTime,
+    #[serde(rename = "End date", default)]
+    end_date: Option<NaiveDate>,
+    #[serde(rename = "End time", default)]
+    end_time: Option<chrono::NaiveTime>,
+    #[serde(rename = "Duration", default)]
+    duration: String,
+}
+
+#[cfg(feature = "csv")]
+impl TogglEvent {
+    fn from_event(event: &Event) -> Self {
+        use chrono::SubsecRound;
+
+        // The duration is calculated from the instants themselves rather than from the local
+        // times, so that it's still correct for events that span a daylight saving time change.
+        let start = event.start.trunc_subsecs(0);
+        let stop = event.stop.map(|stop| stop.trunc_subsecs(0));
+
+        let duration = match stop {
+            Some(stop) => {
+                let seconds = (stop - start).num_seconds();
+                format!(
+                    "{:02}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )
+            }
+            None => String::new(),
+        };
+
+        let start = start.with_timezone(&Local).naive_local();
+        let stop = stop.map(|stop| stop.with_timezone(&Local).naive_local());
+
+        TogglEvent {
+            user: String::new(),
+            email: String::new(),
+            client: String::new(),
+            project: event.project.clone(),
+            task: String::new(),
+            description: event.description.clone(),
+            billable: Some(if event.billable { "Yes" } else { "No" }.to_owned()),
+            start_date: start.date(),
+            start_time: start.time(),
+            end_date: stop.map(|stop| stop.date()),
+            end_time: stop.map(|stop| stop.time()),
+            duration,
+        }
+    }
+
+    fn into_event(self) -> Event {
+        use crate::period::local_instant;
+
+        let start = local_instant(self.start_date, self.start_time);
+        let stop = self
+            .end_time
+            .map(|end_time| local_instant(self.end_date.unwrap_or(self.start_date), end_time));
+
+        Event {
+            stop,
+            description: self.description,
+            project: self.project,
+            billable: self.billable.is_none_or(|billable| billable != "No"),
+            ..Event::new(start)
+        }
+    }
+}
+
+/// Add up the durations of the given events, treating an ongoing event as continuing until `now`.
+fn sum_durations_at<'a, I>(events: I, now: DateTime<Utc>) -> Duration
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    events
+        .into_iter()
+        .map(|e| e.duration_at(now))
+        .fold(Duration::zero(), |acc, next| acc + next)
+}
+
+/// Count the amount of time covered by the given events between `begin` and `end`, including an
+/// ongoing event if there is one, which is treated as continuing until `now`.
+///
+/// If `end` is before `begin`, zero is returned.
+fn count_events_in_range<'a, I>(
+    events: I,
+    begin: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Duration
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    if end < begin {
+        return Duration::zero();
+    }
+
+    events
+        .into_iter()
+        .map(|e| (e.start, e.stop.unwrap_or(now)))
+        .filter(|&(start, stop)| overlaps_range(start, stop, begin, end))
+        .map(|(start, stop)| {
+            let real_begin = std::cmp::max(begin, start);
+            let real_end = std::cmp::min(end, stop);
+
+            // An ongoing event that starts slightly in the future (within the future tolerance)
+            // hasn't covered any time yet, rather than a negative amount.
+            std::cmp::max(real_end - real_begin, Duration::zero())
+        })
+        .fold(Duration::zero(), |acc, next| acc + next)
+}
+
+/// Check whether the period between `start` and `stop` overlaps at least partially with the range
+/// between `begin` and `end`.
+fn overlaps_range(
+    start: DateTime<Utc>,
+    stop: DateTime<Utc>,
+    begin: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> bool {
+    let entirely_before = start < begin && stop < begin;
+    let entirely_after = start > end && stop > end;
+
+    !(entirely_before || entirely_after)
+}
+
+/// Attempt to serialize the given value to a JSON string, indented over multiple lines if `pretty`
+/// is true and compact otherwise.
+fn serialize_json<T>(value: &T, pretty: bool) -> Result<String, SheetError>
+where
+    T: Serialize,
+{
+    if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+    .map_err(SheetError::SerializeSheet)
+}
+
+/// Escape the characters in the given text that have special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Check that the period between `start` and `stop` does not stop before it starts.
+fn check_range(start: DateTime<Utc>, stop: DateTime<Utc>) -> Result<(), SheetError> {
+    if stop < start {
+        Err(SheetError::InvalidTimeRange { start, stop })
+    } else {
+        Ok(())
+    }
+}
+
+/// How [`Sheet::normalize()`][normalize] treats events that stop before they start.
+///
+/// [normalize]: ./struct.Sheet.html#method.normalize
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NormalizeMode {
+    /// Move the stop time of such events to their start time, leaving them with zero length.
+    ClipInvalid,
+    /// Remove such events from the sheet.
+    RemoveInvalid,
+}
+
+/// A summary of the changes made by [`Sheet::normalize()`][normalize].
+///
+/// [normalize]: ./struct.Sheet.html#method.normalize
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct NormalizeReport {
+    /// The number of events removed because they stopped before they started.
+    pub removed: usize,
+    /// The number of duplicate events merged into an earlier event.
+    pub merged: usize,
+    /// The number of events clipped to zero length because they stopped before they started.
+    pub clipped: usize,
+}
+
+/// The figures most often needed to show the state of time-tracking in a sheet, as returned by
+/// [`Sheet::summary()`][summary].
+///
+/// [summary]: ./struct.Sheet.html#method.summary
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SheetSummary {
+    /// Whether or not time is currently being tracked.
+    pub status: SheetStatus,
+    /// The total amount of time tracked, including an ongoing time-tracking period if there is
+    /// one.
+    pub total_duration: Duration,
+    /// The number of events in the sheet, including an ongoing time-tracking period if there is
+    /// one.
+    pub session_count: usize,
+    /// The amount of time tracked since midnight at the start of today.
+    pub today_duration: Duration,
+    /// The amount of time tracked since midnight at the start of Monday this week.
+    pub this_week_duration: Duration,
+    /// The instant as of which the figures were calculated.
+    pub as_of: DateTime<Utc>,
+}
+
+impl Display for SheetSummary {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        self.status.fmt_at(f, self.as_of)?;
+        writeln!(f)?;
+        writeln!(f, "Today:     {}", format_duration(self.today_duration))?;
+        writeln!(f, "This week: {}", format_duration(self.this_week_duration))?;
+        write!(
+            f,
+            "All-time:  {} over {} sessions",
+            format_duration(self.total_duration),
+            self.session_count
+        )
+    }
+}
+
+/// The figures most often needed to describe the time tracked during a range of time, as returned
+/// by [`Sheet::report()`][report].
+///
+/// [report]: ./struct.Sheet.html#method.report
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SheetReport {
+    /// The total amount of time tracked, including an ongoing time-tracking period if there is
+    /// one.
+    pub total_duration: Duration,
+    /// The number of events, including an ongoing time-tracking period if there is one.
+    pub session_count: usize,
+    /// The number of distinct days, in local time, on which at least one event started, once
+    /// events are clipped to the range.
+    pub working_days: u32,
+    /// The mean amount of time tracked per working day, or zero if there were no working days.
+    pub average_per_day: Duration,
+    /// The duration of the longest completed event, or `None` if there were none.
+    pub longest_session: Option<Duration>,
+    /// The duration of the shortest completed event, or `None` if there were none.
+    pub shortest_session: Option<Duration>,
+    /// The length of each break between events, in order.
+    pub gaps: Vec<Duration>,
+    /// The total length of all breaks between events.
+    pub total_break_time: Duration,
+}
+
+impl Display for SheetReport {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let format_session =
+            |session: Option<Duration>| session.map_or_else(|| "none".to_owned(), format_duration);
+
+        writeln!(
+            f,
+            "Total:            {} over {} sessions",
+            format_duration(self.total_duration),
+            self.session_count
+        )?;
+        writeln!(
+            f,
+            "Working days:     {} (average {} per day)",
+            self.working_days,
+            format_duration(self.average_per_day)
+        )?;
+        writeln!(
+            f,
+            "Longest session:  {}",
+            format_session(self.longest_session)
+        )?;
+        writeln!(
+            f,
+            "Shortest session: {}",
+            format_session(self.shortest_session)
+        )?;
+        write!(
+            f,
+            "Breaks:           {} over {} breaks",
+            format_duration(self.total_break_time),
+            self.gaps.len()
+        )
+    }
+}
+
+/// Whether or not time is currently being tracked.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SheetStatus {
+    /// Time is currently being tracked, and has been since the given instant.
+    PunchedIn(DateTime<Utc>),
+    /// Time is not currently being tracked, as of the given instant.
+    PunchedOut(DateTime<Utc>),
+    /// No time has ever been tracked.
+    Empty,
+}
+
+impl SheetStatus {
+    /// Get how long time has been tracked for since last punching in, if currently punched in.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed_at(Utc::now())
+    }
+
+    /// Get how long time had been tracked for at the given instant since last punching in, if
+    /// currently punched in.
+    pub fn elapsed_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match self {
+            SheetStatus::PunchedIn(start) => Some(now - *start),
+            _ => None,
+        }
+    }
+
+    /// Get the instant at which the status last changed, unless no time has ever been tracked.
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        match self {
+            SheetStatus::PunchedIn(time) | SheetStatus::PunchedOut(time) => Some(*time),
+            SheetStatus::Empty => None,
+        }
+    }
+}
+
+impl SheetStatus {
+    /// Format the status as its [`Display`][display] implementation does, but with the time
+    /// elapsed since punching in measured up to the given instant.
+    ///
+    /// [display]: #impl-Display-for-SheetStatus
+    fn fmt_at(&self, f: &mut Formatter, now: DateTime<Utc>) -> FmtResult {
+        match self {
+            SheetStatus::PunchedIn(start) => write!(
+                f,
+                "Punched in since {} ({} ago)",
+                start.format("%H:%M UTC"),
+                format_duration(self.elapsed_at(now).unwrap_or_else(Duration::zero)),
+            ),
+            SheetStatus::PunchedOut(stop) => {
+                write!(f, "Punched out at {}", stop.format("%H:%M UTC"))
+            }
+            SheetStatus::Empty => write!(f, "No time tracked yet."),
+        }
+    }
+}
+
+impl Display for SheetStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        self.fmt_at(f, Utc::now())
+    }
+}
+
+/// Errors arising through the use of [`Sheet`][sheet].
+///
+/// [sheet]: ./struct.Sheet.html
+#[derive(Error, Debug)]
+pub enum SheetError {
+    #[error("already punched in at {0}")]
+    PunchedIn(DateTime<Utc>),
+    #[error("not punched in, last punched out at {0}")]
+    PunchedOut(DateTime<Utc>),
+    #[error("not punched in, no punch-ins recorded")]
+    NoPunches,
+    #[error("{0} is in the future")]
+    FutureTime(DateTime<Utc>),
+    #[error("no event at index {0}")]
+    IndexOutOfBounds(usize),
+    #[error("invalid time range: stop time {stop} is before start time {start}")]
+    InvalidTimeRange {
+        start: DateTime<Utc>,
+        stop: DateTime<Utc>,
+    },
+    #[error("event at index {0} is ongoing")]
+    OngoingEvent(usize),
+    #[error("{0} is not within the event")]
+    OutsideEvent(DateTime<Utc>),
+    #[error("event has no stop time")]
+    IncompleteEvent,
+    #[error("event overlaps with existing event at index {0}")]
+    Overlapping(usize),
+    #[error("{0} is out of order with existing events")]
+    OutOfOrder(DateTime<Utc>),
+    #[error("moving {0} would take it outside the range of representable times")]
+    OutOfRange(DateTime<Utc>),
+    #[error("unable to find sheet file")]
+    FindSheet,
+    #[error("unable to open sheet file")]
+    OpenSheet(#[source] std::io::Error),
+    #[error("unable to read sheet file")]
+    ReadSheet(#[source] std::io::Error),
+    #[error("unable to parse sheet")]
+    ParseSheet(#[source] serde_json::Error),
+    #[error("unable to serialize sheet")]
+    SerializeSheet(#[source] serde_json::Error),
+    #[error("unable to read sheet directory")]
+    ReadSheetDir(#[source] std::io::Error),
+    #[error("invalid sheet name {0:?}")]
+    InvalidSheetName(String),
+    #[error("unknown sheet file format {0:?}")]
+    UnknownFormat(String),
+    #[error("unable to create data directory")]
+    CreateDataDir(#[source] std::io::Error),
+    #[error("unable to back up sheet file")]
+    BackupSheet(#[source] std::io::Error),
+    #[error("unable to lock sheet file")]
+    LockSheet(#[source] std::io::Error),
+    #[error("sheet file is locked by another process")]
+    SheetLocked,
+    #[error("unable to create temporary sheet file")]
+    CreateTempFile(#[source] std::io::Error),
+    #[error("unable to replace sheet file with temporary sheet file")]
+    RenameTempFile(#[source] std::io::Error),
+    #[cfg(feature = "toml")]
+    #[error("unable to parse TOML sheet")]
+    ParseTomlSheet(#[source] toml::de::Error),
+    #[cfg(feature = "toml")]
+    #[error("unable to serialize TOML sheet")]
+    SerializeTomlSheet(#[source] toml::ser::Error),
+    #[cfg(feature = "csv")]
+    #[error("unable to parse CSV sheet")]
+    ParseCsv(#[source] csv::Error),
+    #[cfg(feature = "csv")]
+    #[error("unable to write CSV sheet")]
+    WriteCsv(#[source] csv::Error),
+    #[error("unable to write sheet to file")]
+    WriteSheet(#[source] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Mutex, PoisonError},
+    };
+
+    use super::*;
+    use crate::{
+        storage::MemoryBackend,
+        testing::{completed, date, local, time, utc},
+    };
+
+    /// A writer that always fails.
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("failing writer"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A sheet of events starting two hours apart from midnight on Monday 2024-01-15, in UTC, each
+    /// lasting the given number of minutes.
+    fn spaced_sessions<I>(minutes: I) -> Sheet
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        minutes
+            .into_iter()
+            .zip(0..)
+            .map(|(length, i)| {
+                let start = utc(0, 0) + Duration::hours(2 * i);
+                completed(start, start + Duration::minutes(length))
+            })
+            .collect()
+    }
+
+    /// An event starting at noon local time on the given date, lasting the given number of hours.
+    fn hours_from_noon(day: NaiveDate, hours: i64) -> Event {
+        let noon = crate::period::local_instant(day, time(12, 0));
+        completed(noon, noon + Duration::hours(hours))
+    }
+
+    /// A sheet with a completed event whose description needs escaping, and an ongoing event.
+    fn table_sheet() -> Sheet {
+        Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 30)),
+                ..Event::with_description(utc(9, 0), "Tests | <docs>\nand more")
+            },
+            Event::new(utc(11, 0)),
+        ])
+    }
+
+    /// A hand-edited sheet, out of order, with a duplicate event and one that stops before it
+    /// starts.
+    fn hand_edited_sheet() -> Sheet {
+        Sheet::from_events_unchecked(vec![
+            completed(utc(11, 0), utc(12, 0)),
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(14, 0), utc(13, 0)),
+            completed(utc(9, 0), utc(10, 0)),
+        ])
+    }
+
+    /// Hash the given value with the standard library's default hasher.
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// A value that always fails to serialize.
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            Err(serde::ser::Error::custom("unserializable"))
+        }
+    }
+
+    /// Held by tests that use the default location while they do, since they change the
+    /// environment variable that determines it.
+    static DATA_HOME: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn default_sheets_are_equal() {
+        assert_eq!(Sheet::default(), Sheet::default());
+    }
+
+    #[test]
+    fn collected_sheet_equals_original() {
+        let mut sheet = Sheet::default();
+        sheet.events.push(completed(utc(9, 0), utc(10, 0)));
+
+        let collected: Sheet = sheet.events.iter().cloned().collect();
+
+        assert_eq!(collected, sheet);
+    }
+
+    #[test]
+    fn names_and_descriptions_are_compared() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        let mut named = sheet.clone();
+        named.set_name("work");
+        assert_ne!(named, sheet);
+        assert_eq!(named.cmp(&sheet), Ordering::Greater);
+
+        let mut described = sheet.clone();
+        described.set_description("Client work");
+        assert_ne!(described, sheet);
+        assert_ne!(hash_of(&described), hash_of(&sheet));
+    }
+
+    #[test]
+    fn written_sheet_equals_original() {
+        let sheet = Sheet::named("work");
+
+        let mut written = sheet.clone();
+        written.write_to_writer(Vec::new()).unwrap();
+
+        assert_ne!(written.updated_at(), sheet.updated_at());
+        assert_eq!(written, sheet);
+        assert_eq!(hash_of(&written), hash_of(&sheet));
+    }
+
+    #[test]
+    fn writing_to_writer_records_updated_at() {
+        let mut sheet = Sheet::default();
+        assert_eq!(sheet.updated_at(), None);
+
+        let mut written = Vec::new();
+        sheet.write_to_writer(&mut written).unwrap();
+
+        let updated_at = sheet.updated_at().expect("updated_at is set by writing");
+        let parsed = Sheet::from_json_str(std::str::from_utf8(&written).unwrap()).unwrap();
+        assert_eq!(parsed.updated_at(), Some(updated_at));
+    }
+
+    #[test]
+    fn writing_to_path_records_updated_at() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.json");
+
+        let mut sheet = Sheet::default();
+        sheet.write_to_path(&path).unwrap();
+
+        assert!(sheet.updated_at().is_some());
+        assert_eq!(
+            Sheet::load_from_path(&path).unwrap().updated_at(),
+            sheet.updated_at()
+        );
+    }
+
+    #[test]
+    fn writing_to_backend_records_updated_at() {
+        let backend = MemoryBackend::default();
+
+        let mut sheet = Sheet::default();
+        sheet.write_with(&backend).unwrap();
+
+        assert!(sheet.updated_at().is_some());
+        assert_eq!(backend.load().unwrap().updated_at(), sheet.updated_at());
+    }
+
+    #[test]
+    fn failed_write_leaves_updated_at_unchanged() {
+        let mut sheet = Sheet::default();
+
+        assert!(sheet.write_to_writer(FailingWriter).is_err());
+        assert_eq!(sheet.updated_at(), None);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn exporting_leaves_updated_at_unchanged() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        sheet.to_csv_writer(Vec::new()).unwrap();
+        sheet.to_toggl_csv_writer(Vec::new()).unwrap();
+        assert_eq!(sheet.updated_at(), None);
+    }
+
+    #[test]
+    fn edit_event_replaces_given_times() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        sheet.edit_event(0, Some(utc(8, 30)), None).unwrap();
+        assert_eq!(sheet.events[0], completed(utc(8, 30), utc(10, 0)));
+
+        sheet.edit_event(0, None, Some(Some(utc(11, 0)))).unwrap();
+        assert_eq!(sheet.events[0], completed(utc(8, 30), utc(11, 0)));
+
+        sheet.edit_event(0, None, Some(None)).unwrap();
+        assert_eq!(sheet.events[0], Event::new(utc(8, 30)));
+    }
+
+    #[test]
+    fn edit_event_rejects_stop_before_start() {
+        let original = completed(utc(9, 0), utc(10, 0));
+        let mut sheet = Sheet::from_events_unchecked(vec![original.clone()]);
+
+        assert!(matches!(
+            sheet.edit_event(0, Some(utc(10, 0)), Some(Some(utc(9, 0)))),
+            Err(SheetError::InvalidTimeRange { .. })
+        ));
+        assert!(matches!(
+            sheet.edit_event(0, Some(utc(10, 30)), None),
+            Err(SheetError::InvalidTimeRange { .. })
+        ));
+        assert_eq!(sheet.events, vec![original]);
+
+        sheet.edit_event(0, Some(utc(10, 0)), None).unwrap();
+        assert_eq!(sheet.events, vec![completed(utc(10, 0), utc(10, 0))]);
+    }
+
+    #[test]
+    fn invalid_time_range_message_says_stop_is_before_start() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        let error = sheet
+            .edit_event(0, None, Some(Some(utc(8, 0))))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "invalid time range: stop time 2024-01-15 08:00:00 UTC is before start time \
+             2024-01-15 09:00:00 UTC"
+        );
+    }
+
+    #[test]
+    fn edit_event_rejects_missing_index() {
+        let mut sheet = Sheet::default();
+
+        assert!(matches!(
+            sheet.edit_event(0, Some(utc(9, 0)), None),
+            Err(SheetError::IndexOutOfBounds(0))
+        ));
+    }
+
+    #[test]
+    fn writing_to_writer_writes_json_string() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+
+        let mut written = Vec::new();
+        sheet.write_to_writer(&mut written).unwrap();
+
+        assert_eq!(String::from_utf8(written).unwrap(), sheet.to_json_string());
+    }
+
+    #[test]
+    fn write_failures_are_returned_as_errors() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert!(matches!(
+            sheet.write_to_writer(FailingWriter),
+            Err(SheetError::WriteSheet(_))
+        ));
+    }
+
+    #[test]
+    fn serialization_failures_are_returned_as_errors() {
+        for pretty in [false, true] {
+            assert!(matches!(
+                serialize_json(&Unserializable, pretty),
+                Err(SheetError::SerializeSheet(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn toggl_csv_round_trips() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 30)),
+                project: Some("punch-clock".to_owned()),
+                ..Event::with_description(utc(9, 0), "Write tests")
+            },
+            Event {
+                billable: false,
+                ..Event::new(utc(11, 0))
+            },
+        ]);
+
+        let mut csv = Vec::new();
+        sheet.to_toggl_csv_writer(&mut csv).unwrap();
+        let imported = Sheet::from_toggl_csv_reader(csv.as_slice()).unwrap();
+
+        assert_eq!(imported, sheet);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn toggl_csv_times_are_truncated_to_seconds() {
+        let start = utc(9, 0) + Duration::milliseconds(123);
+        let sheet =
+            Sheet::from_events_unchecked(vec![completed(start, start + Duration::seconds(90))]);
+
+        let mut csv = Vec::new();
+        sheet.to_toggl_csv_writer(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+
+        assert!(!csv.contains(".123"));
+        assert!(csv.contains("00:01:30"));
+        assert_eq!(
+            Sheet::from_toggl_csv_reader(csv.as_bytes()).unwrap().events,
+            vec![completed(utc(9, 0), utc(9, 1) + Duration::seconds(30))]
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn toggl_csv_duration_is_elapsed_time_across_dst_change() {
+        use chrono::TimeZone;
+
+        // Many time zones change to or from daylight saving time during one of these events, so
+        // the local times at either end are an hour more or less apart than the events last.
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(
+                Utc.with_ymd_and_hms(2024, 3, 30, 22, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 31, 4, 0, 0).unwrap(),
+            ),
+            completed(
+                Utc.with_ymd_and_hms(2024, 11, 2, 22, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 11, 3, 10, 0, 0).unwrap(),
+            ),
+        ]);
+
+        let mut csv = Vec::new();
+        sheet.to_toggl_csv_writer(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+
+        assert!(csv.contains(",06:00:00"));
+        assert!(csv.contains(",12:00:00"));
+    }
+
+    #[test]
+    fn billable_and_non_billable_durations_add_up_to_total() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event {
+                stop: Some(utc(11, 30)),
+                billable: false,
+                ..Event::new(utc(10, 0))
+            },
+            Event::new(utc(12, 0)),
+        ]);
+        let now = utc(12, 45);
+
+        assert_eq!(sheet.billable_duration_at(now), Duration::minutes(105));
+        assert_eq!(sheet.non_billable_duration_at(now), Duration::minutes(90));
+        assert_eq!(
+            sheet.billable_duration_at(now) + sheet.non_billable_duration_at(now),
+            sheet.total_duration_at(now)
+        );
+    }
+
+    #[test]
+    fn project_groups_add_up_to_total() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0)),
+                ..Event::for_project(utc(9, 0), "a")
+            },
+            completed(utc(10, 0), utc(10, 45)),
+            Event {
+                stop: Some(utc(12, 0)),
+                ..Event::for_project(utc(11, 0), "b")
+            },
+            Event::for_project(utc(13, 0), "a"),
+        ]);
+        let now = utc(13, 20);
+
+        let groups = sheet.group_by_project();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups[&Some("a".to_owned())].total_duration_at(now),
+            Duration::minutes(80)
+        );
+        assert_eq!(
+            groups[&None].events,
+            vec![completed(utc(10, 0), utc(10, 45))]
+        );
+
+        let sum = groups
+            .values()
+            .map(|group| group.total_duration_at(now))
+            .fold(Duration::zero(), |acc, next| acc + next);
+        assert_eq!(sum, sheet.total_duration_at(now));
+    }
+
+    #[cfg(feature = "ical")]
+    #[test]
+    fn ical_output_is_a_calendar_of_every_event() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0)),
+                ..Event::with_description(utc(9, 0), "Write tests")
+            },
+            Event::new(utc(11, 0)),
+        ]);
+
+        let mut ical = Vec::new();
+        sheet.to_ical_writer(&mut ical).unwrap();
+        let ical = String::from_utf8(ical).unwrap();
+
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.contains("VERSION:2.0\r\n"));
+        assert!(ical.trim_end().ends_with("END:VCALENDAR"));
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ical.contains("DTSTART:20240115T090000Z"));
+        assert!(ical.contains("DTEND:20240115T100000Z"));
+        assert!(ical.contains("DTSTART:20240115T110000Z"));
+        assert!(ical.contains("SUMMARY:Write tests"));
+    }
+
+    #[test]
+    fn count_range_sorted_matches_count_range() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(10, 30), utc(12, 0)),
+            completed(utc(13, 0), utc(13, 15)),
+            Event::new(utc(14, 0)),
+        ]);
+        assert!(sheet.is_sorted());
+
+        let ranges = [
+            (utc(0, 0), utc(23, 0)),
+            (utc(9, 30), utc(11, 0)),
+            (utc(10, 0), utc(10, 30)),
+            (utc(12, 0), utc(13, 0)),
+            (utc(13, 5), utc(13, 10)),
+            (utc(14, 30), utc(15, 0)),
+            (utc(11, 0), utc(9, 0)),
+        ];
+        for (begin, end) in ranges {
+            assert_eq!(
+                sheet.count_range_sorted(begin, end),
+                sheet.count_range(begin, end),
+                "{} to {}",
+                begin,
+                end
+            );
+        }
+    }
+
+    #[test]
+    fn apply_modifies_every_event_in_order() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+
+        let mut seen = Vec::new();
+        sheet.apply(|event| {
+            seen.push(event.start);
+            event.billable = false;
+        });
+
+        assert_eq!(seen, vec![utc(9, 0), utc(11, 0)]);
+        assert!(sheet.events.iter().all(|event| !event.billable));
+    }
+
+    #[test]
+    fn shift_all_by_moves_every_time() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+
+        sheet.shift_all_by(Duration::hours(2)).unwrap();
+        assert_eq!(
+            sheet.events,
+            vec![completed(utc(11, 0), utc(12, 0)), Event::new(utc(13, 0))]
+        );
+
+        sheet.shift_all_by(Duration::minutes(-150)).unwrap();
+        assert_eq!(
+            sheet.events,
+            vec![completed(utc(8, 30), utc(9, 30)), Event::new(utc(10, 30))]
+        );
+    }
+
+    #[test]
+    fn shift_all_by_rejects_unrepresentable_times() {
+        let original = vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(
+                DateTime::<Utc>::MAX_UTC - Duration::hours(1),
+                DateTime::<Utc>::MAX_UTC,
+            ),
+        ];
+        let mut sheet = Sheet::from_events_unchecked(original.clone());
+
+        assert!(matches!(
+            sheet.shift_all_by(Duration::minutes(30)),
+            Err(SheetError::OutOfRange(time)) if time == DateTime::<Utc>::MAX_UTC
+        ));
+        assert_eq!(sheet.events, original);
+    }
+
+    #[test]
+    fn summary_shows_elapsed_time_as_of_its_instant() {
+        let summary = SheetSummary {
+            status: SheetStatus::PunchedIn(utc(9, 0)),
+            total_duration: Duration::minutes(195),
+            session_count: 2,
+            today_duration: Duration::minutes(195),
+            this_week_duration: Duration::minutes(195),
+            as_of: utc(11, 15),
+        };
+
+        assert_eq!(
+            summary.to_string().lines().next(),
+            Some("Punched in since 09:00 UTC (2h 15m ago)")
+        );
+    }
+
+    #[test]
+    fn summary_is_calculated_as_of_one_instant() {
+        let sheet = Sheet::from_events_unchecked(vec![Event::new(Utc::now() - Duration::hours(1))]);
+
+        let summary = sheet.summary();
+
+        assert_eq!(
+            summary.status.elapsed_at(summary.as_of),
+            Some(summary.total_duration)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn write_default_creates_missing_data_dir() {
+        let _data_home = DATA_HOME.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_home = dir.path().join("missing");
+        std::env::set_var("XDG_DATA_HOME", &data_home);
+
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        sheet.write_default().unwrap();
+
+        assert_eq!(Sheet::default_dir().unwrap(), data_home.join("punchclock"));
+        assert!(Sheet::default_loc().unwrap().is_file());
+        assert_eq!(Sheet::load_default().unwrap(), sheet);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn try_load_default_fails_while_default_sheet_is_locked() {
+        let _data_home = DATA_HOME.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var("XDG_DATA_HOME", dir.path());
+
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        sheet.write_default().unwrap();
+
+        let lock_file = File::create(Sheet::default_dir().unwrap().join("sheet.lock")).unwrap();
+        FileExt::lock_exclusive(&lock_file).unwrap();
+        assert!(matches!(
+            Sheet::try_load_default(),
+            Err(SheetError::SheetLocked)
+        ));
+
+        FileExt::unlock(&lock_file).unwrap();
+        assert_eq!(Sheet::try_load_default().unwrap(), sheet);
+    }
+
+    #[test]
+    fn future_tolerance_is_ignored_by_equality() {
+        let sheet = Sheet::from_events_unchecked(Vec::new());
+        let mut tolerant = sheet.clone();
+        tolerant.set_future_tolerance(Duration::hours(1));
+
+        assert_eq!(sheet, tolerant);
+        assert_eq!(sheet.cmp(&tolerant), Ordering::Equal);
+    }
+
+    #[test]
+    fn total_duration_includes_ongoing_event() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(10, 30), utc(11, 0)),
+            Event::new(utc(12, 0)),
+        ]);
+
+        assert_eq!(sheet.total_duration_at(utc(12, 15)), Duration::minutes(105));
+        assert!(sheet.total_duration() > Duration::minutes(105));
+        assert_eq!(Sheet::default().total_duration(), Duration::zero());
+    }
+
+    #[test]
+    fn daily_breakdown_splits_events_at_midnight() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(local(2024, 1, 15, 9, 0), local(2024, 1, 15, 10, 0)),
+            completed(local(2024, 1, 15, 22, 0), local(2024, 1, 16, 2, 30)),
+        ]);
+
+        let breakdown = sheet.daily_breakdown();
+
+        assert_eq!(
+            breakdown.into_iter().collect::<Vec<_>>(),
+            vec![
+                (date(2024, 1, 15), Duration::hours(3)),
+                (date(2024, 1, 16), Duration::minutes(150)),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_breakdown_splits_events_between_weeks() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(local(2024, 1, 10, 9, 0), local(2024, 1, 10, 10, 0)),
+            completed(local(2024, 1, 16, 9, 0), local(2024, 1, 16, 11, 0)),
+            completed(local(2024, 1, 21, 22, 0), local(2024, 1, 22, 1, 0)),
+        ]);
+
+        let breakdown = sheet.weekly_breakdown();
+
+        assert_eq!(
+            breakdown.into_iter().collect::<Vec<_>>(),
+            vec![
+                (date(2024, 1, 8), Duration::hours(1)),
+                (date(2024, 1, 15), Duration::hours(4)),
+                (date(2024, 1, 22), Duration::hours(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn session_statistics_of_single_session() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert_eq!(sheet.average_session_duration(), Some(Duration::hours(1)));
+        assert_eq!(sheet.longest_session(), Some(&sheet.events[0]));
+        assert_eq!(sheet.shortest_session(), Some(&sheet.events[0]));
+    }
+
+    #[test]
+    fn session_statistics_of_several_sessions() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(9, 30)),
+            completed(utc(10, 0), utc(12, 0)),
+            completed(utc(13, 0), utc(13, 30)),
+            completed(utc(14, 0), utc(15, 0)),
+            Event::new(utc(16, 0)),
+        ]);
+
+        assert_eq!(
+            sheet.average_session_duration(),
+            Some(Duration::minutes(60))
+        );
+        assert_eq!(sheet.longest_session(), Some(&sheet.events[1]));
+        assert_eq!(sheet.shortest_session(), Some(&sheet.events[0]));
+    }
+
+    #[test]
+    fn session_statistics_without_sessions() {
+        let empty = Sheet::default();
+        let ongoing = Sheet::from_events_unchecked(vec![Event::new(utc(9, 0))]);
+
+        for sheet in [empty, ongoing] {
+            assert_eq!(sheet.average_session_duration(), None);
+            assert_eq!(sheet.longest_session(), None);
+            assert_eq!(sheet.shortest_session(), None);
+        }
+    }
+
+    #[test]
+    fn median_session_duration_of_odd_and_even_counts() {
+        let cases: [(&[i64], Option<i64>); 6] = [
+            (&[], None),
+            (&[30], Some(30)),
+            (&[30, 90], Some(30)),
+            (&[90, 10, 30], Some(30)),
+            (&[40, 10, 30, 20], Some(20)),
+            (&[5, 60, 15, 45, 30], Some(30)),
+        ];
+
+        for (minutes, expected) in cases {
+            let sheet = spaced_sessions(minutes.iter().copied());
+
+            assert_eq!(
+                sheet.median_session_duration(),
+                expected.map(Duration::minutes),
+                "{:?}",
+                minutes
+            );
+        }
+    }
+
+    #[test]
+    fn session_counts() {
+        let cases = [
+            (Sheet::default(), 0, 0),
+            (
+                Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]),
+                1,
+                0,
+            ),
+            (
+                Sheet::from_events_unchecked(vec![
+                    completed(utc(9, 0), utc(10, 0)),
+                    completed(utc(11, 0), utc(12, 0)),
+                    Event::new(utc(13, 0)),
+                ]),
+                2,
+                1,
+            ),
+        ];
+
+        for (sheet, completed_count, ongoing_count) in cases {
+            assert_eq!(sheet.completed_session_count(), completed_count);
+            assert_eq!(sheet.ongoing_session_count(), ongoing_count);
+            assert_eq!(
+                sheet.session_count(),
+                sheet.completed_session_count() + sheet.ongoing_session_count()
+            );
+        }
+    }
+
+    #[test]
+    fn range_queries_distinguish_partial_overlap_from_containment() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(8, 0), utc(9, 30)),
+            completed(utc(10, 0), utc(11, 0)),
+            completed(utc(11, 30), utc(13, 0)),
+            completed(utc(14, 0), utc(15, 0)),
+        ]);
+        let (begin, end) = (utc(9, 0), utc(12, 0));
+
+        assert_eq!(
+            sheet.events_in_range(begin, end),
+            vec![&sheet.events[0], &sheet.events[1], &sheet.events[2]]
+        );
+        assert_eq!(
+            sheet.events_strictly_within_range(begin, end),
+            vec![&sheet.events[1]]
+        );
+    }
+
+    #[test]
+    fn range_queries_include_events_matching_range_exactly() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert_eq!(sheet.events_in_range(utc(9, 0), utc(10, 0)).len(), 1);
+        assert_eq!(
+            sheet
+                .events_strictly_within_range(utc(9, 0), utc(10, 0))
+                .len(),
+            1
+        );
+        assert!(sheet
+            .events_strictly_within_range(utc(9, 1), utc(10, 0))
+            .is_empty());
+    }
+
+    #[test]
+    fn delete_event_removes_and_returns_event() {
+        let first = completed(utc(9, 0), utc(10, 0));
+        let second = completed(utc(11, 0), utc(12, 0));
+        let mut sheet = Sheet::from_events_unchecked(vec![first.clone(), second.clone()]);
+
+        assert_eq!(sheet.delete_event(0).unwrap(), first);
+        assert_eq!(sheet.len(), 1);
+        assert_eq!(sheet.events, vec![second]);
+    }
+
+    #[test]
+    fn delete_event_rejects_missing_index() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert!(matches!(
+            sheet.delete_event(1),
+            Err(SheetError::IndexOutOfBounds(1))
+        ));
+        assert_eq!(sheet.len(), 1);
+    }
+
+    #[test]
+    fn amend_last_punch_in() {
+        let mut sheet = Sheet::default();
+        assert!(matches!(
+            sheet.amend_last_punch_in(utc(9, 0)),
+            Err(SheetError::NoPunches)
+        ));
+
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        assert!(matches!(
+            sheet.amend_last_punch_in(utc(10, 30)),
+            Err(SheetError::InvalidTimeRange { .. })
+        ));
+        assert_eq!(sheet.events, vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert_eq!(sheet.amend_last_punch_in(utc(8, 45)).unwrap(), utc(8, 45));
+        assert_eq!(sheet.events, vec![completed(utc(8, 45), utc(10, 0))]);
+
+        let mut sheet = Sheet::from_events_unchecked(vec![Event::new(utc(9, 0))]);
+        assert_eq!(sheet.amend_last_punch_in(utc(9, 15)).unwrap(), utc(9, 15));
+        assert_eq!(sheet.events, vec![Event::new(utc(9, 15))]);
+    }
+
+    #[test]
+    fn amend_last_punch_out() {
+        let mut sheet = Sheet::default();
+        assert!(matches!(
+            sheet.amend_last_punch_out(utc(10, 0)),
+            Err(SheetError::NoPunches)
+        ));
+
+        let mut sheet = Sheet::from_events_unchecked(vec![Event::new(utc(9, 0))]);
+        assert!(matches!(
+            sheet.amend_last_punch_out(utc(10, 0)),
+            Err(SheetError::PunchedIn(start)) if start == utc(9, 0)
+        ));
+        assert_eq!(sheet.events, vec![Event::new(utc(9, 0))]);
+
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        assert!(matches!(
+            sheet.amend_last_punch_out(utc(8, 0)),
+            Err(SheetError::InvalidTimeRange { .. })
+        ));
+        assert_eq!(sheet.events, vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert_eq!(
+            sheet.amend_last_punch_out(utc(10, 30)).unwrap(),
+            utc(10, 30)
+        );
+        assert_eq!(sheet.events, vec![completed(utc(9, 0), utc(10, 30))]);
+    }
+
+    #[test]
+    fn find_overlaps_ignores_adjacent_events() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(10, 0), utc(11, 0)),
+        ]);
+
+        assert_eq!(sheet.find_overlaps(), vec![]);
+        assert!(!sheet.has_overlaps());
+    }
+
+    #[test]
+    fn find_overlaps_finds_contained_events() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(12, 0)),
+            completed(utc(10, 0), utc(11, 0)),
+        ]);
+
+        assert_eq!(sheet.find_overlaps(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn find_overlaps_finds_partially_overlapping_events() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 30)),
+            completed(utc(10, 0), utc(11, 30)),
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(11, 45)),
+        ]);
+
+        assert_eq!(sheet.find_overlaps(), vec![(0, 1), (1, 2), (2, 3)]);
+        assert!(sheet.has_overlaps());
+    }
+
+    #[test]
+    fn merge_overlapping_events_merges_chains() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(11, 0), utc(12, 0)),
+            completed(utc(9, 0), utc(10, 30)),
+            completed(utc(10, 0), utc(11, 30)),
+            completed(utc(11, 45), utc(12, 15)),
+            completed(utc(14, 0), utc(15, 0)),
+        ]);
+
+        sheet.merge_overlapping_events();
+
+        assert_eq!(
+            sheet.events,
+            vec![
+                completed(utc(9, 0), utc(12, 15)),
+                completed(utc(14, 0), utc(15, 0)),
+            ]
+        );
+        assert!(!sheet.has_overlaps());
+    }
+
+    #[test]
+    fn merge_overlapping_events_keeps_merged_events_ongoing() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(9, 30)),
+            completed(utc(11, 0), utc(12, 0)),
+        ]);
+
+        sheet.merge_overlapping_events();
+
+        assert_eq!(sheet.events, vec![Event::new(utc(9, 0))]);
+    }
+
+    #[test]
+    fn interrupted_write_leaves_existing_file_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.json");
+
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        sheet.write_to_path(&path).unwrap();
+        let original = fs::read_to_string(&path).unwrap();
+
+        let result = write_atomically(&path, |file| {
+            file.write_all(&original.as_bytes()[..original.len() / 2])
+                .map_err(SheetError::WriteSheet)?;
+            Err(SheetError::WriteSheet(io::Error::other("interrupted")))
+        });
+
+        assert!(matches!(result, Err(SheetError::WriteSheet(_))));
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert!(!temp_path_for(&path).exists());
+        assert_eq!(Sheet::load_from_path(&path).unwrap(), sheet);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.toml");
+
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0)),
+                description: Some("Write tests".to_owned()),
+                project: Some("punch-clock".to_owned()),
+                tags: vec!["dev".to_owned()],
+                billable: false,
+                ..Event::new(utc(9, 0))
+            },
+            Event::new(utc(11, 0)),
+        ]);
+        sheet.set_name("work");
+        sheet.write_to_toml_path(&path).unwrap();
+
+        let loaded = Sheet::load_from_toml_path(&path).unwrap();
+        assert_eq!(loaded.events, sheet.events);
+        assert_eq!(loaded.name(), Some("work"));
+        assert_eq!(loaded.updated_at(), sheet.updated_at());
+        assert_eq!(Sheet::from_file_detecting_format(&path).unwrap(), sheet);
+    }
+
+    #[test]
+    fn pretty_output_is_multi_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.json");
+
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        sheet.write_to_path_pretty(&path).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.lines().count() > 1);
+        assert!(written.contains("\n  \"events\""));
+        assert!(!sheet.to_json_string().contains('\n'));
+        assert_eq!(Sheet::load_from_path(&path).unwrap(), sheet);
+    }
+
+    #[test]
+    fn json_strings_round_trip() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0) + Duration::milliseconds(250)),
+                ..Event::with_description(utc(9, 0), "Write tests")
+            },
+            Event::new(utc(11, 0)),
+        ]);
+
+        assert_eq!(
+            Sheet::from_json_str(&sheet.to_json_string()).unwrap(),
+            sheet
+        );
+        assert_eq!(
+            Sheet::from_json_str(&sheet.to_json_string_pretty()).unwrap(),
+            sheet
+        );
+    }
+
+    #[test]
+    fn empty_json_string_is_empty_sheet() {
+        assert_eq!(Sheet::from_json_str("").unwrap(), Sheet::default());
+    }
+
+    #[test]
+    fn merge_keeps_overlapping_events() {
+        let ours = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(11, 0))]);
+        let theirs = Sheet::from_events_unchecked(vec![completed(utc(10, 0), utc(12, 0))]);
+
+        assert_eq!(
+            ours.merge(&theirs).events,
+            vec![
+                completed(utc(9, 0), utc(11, 0)),
+                completed(utc(10, 0), utc(12, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_removes_duplicates() {
+        let ours = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+        ]);
+        let theirs = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0)),
+                ..Event::with_description(utc(9, 0), "duplicate")
+            },
+            completed(utc(13, 0), utc(14, 0)),
+        ]);
+
+        let mut merged = ours.clone();
+        merged.merge_in_place(&theirs);
+
+        assert_eq!(
+            merged.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(11, 0), utc(12, 0)),
+                completed(utc(13, 0), utc(14, 0)),
+            ]
+        );
+        assert_eq!(ours.merge(&theirs), merged);
+    }
+
+    #[test]
+    fn merge_combines_disjoint_sheets_in_order() {
+        let mut ours = Sheet::named("work");
+        ours.events.push(completed(utc(13, 0), utc(14, 0)));
+        let theirs = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(15, 0), utc(16, 0)),
+        ]);
+
+        let merged = ours.merge(&theirs);
+
+        assert_eq!(
+            merged.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(13, 0), utc(14, 0)),
+                completed(utc(15, 0), utc(16, 0)),
+            ]
+        );
+        assert_eq!(merged.name(), Some("work"));
+    }
+
+    #[test]
+    fn archive_before_partitions_events() {
+        let original = Sheet::from_events_unchecked(vec![
+            completed(utc(8, 0), utc(9, 0)),
+            completed(utc(9, 30), utc(10, 0)),
+            completed(utc(12, 0), utc(13, 0)),
+            Event::new(utc(14, 0)),
+        ]);
+
+        let mut remaining = original.clone();
+        let archived = remaining.archive_before(utc(11, 0));
+
+        assert_eq!(original.len(), remaining.len() + archived.len());
+        assert_eq!(archived.events, original.events[..2]);
+        assert_eq!(remaining.events, original.events[2..]);
+    }
+
+    #[test]
+    fn archive_before_keeps_straddling_events_intact() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(8, 0), utc(9, 0)),
+            completed(utc(10, 0), utc(12, 0)),
+            Event::new(utc(10, 30)),
+        ]);
+
+        let archived = sheet.archive_before(utc(11, 0));
+
+        assert_eq!(archived.events, vec![completed(utc(8, 0), utc(9, 0))]);
+        assert_eq!(
+            sheet.events,
+            vec![completed(utc(10, 0), utc(12, 0)), Event::new(utc(10, 30))]
+        );
+    }
+
+    #[test]
+    fn sheet_round_trips_through_iterators() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(13, 0)),
+        ]);
+
+        let borrowed: Vec<&Event> = (&sheet).into_iter().collect();
+        assert_eq!(borrowed, sheet.events.iter().collect::<Vec<_>>());
+
+        let collected: Sheet = sheet.clone().into_iter().collect();
+        assert_eq!(collected, sheet);
+
+        let completed_only: Sheet = sheet
+            .clone()
+            .into_iter()
+            .filter(Event::is_complete)
+            .collect();
+        assert_eq!(completed_only.events, sheet.events[..2]);
+    }
+
+    #[test]
+    fn extend_appends_events() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        sheet.extend(vec![
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(13, 0)),
+        ]);
+
+        assert_eq!(
+            sheet.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(11, 0), utc(12, 0)),
+                Event::new(utc(13, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn display_aligns_columns_and_shows_total() {
+        let sheet = spaced_sessions((0..11).map(|i| 5 * i + 1));
+
+        let table = sheet.to_string();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), sheet.len() + 2);
+        assert!(lines
+            .iter()
+            .all(|line| line.chars().count() == lines[0].chars().count()));
+        assert!(lines[0].starts_with(" #  Date"));
+        assert!(lines[0].ends_with("Duration"));
+        assert!(lines[11].starts_with("10  2024-01-15  20:00:00  20:51:00"));
+        assert!(lines[11].ends_with("51m"));
+
+        let total = lines.last().unwrap();
+        assert!(total.trim_start().starts_with("Total"));
+        assert!(total.ends_with(&format_duration(sheet.total_duration())));
+    }
+
+    #[test]
+    fn status_elapsed_and_since() {
+        let punched_in = SheetStatus::PunchedIn(utc(9, 0));
+        assert_eq!(
+            punched_in.elapsed_at(utc(11, 15)),
+            Some(Duration::minutes(135))
+        );
+        assert_eq!(punched_in.since(), Some(utc(9, 0)));
+
+        let punched_out = SheetStatus::PunchedOut(utc(10, 0));
+        assert_eq!(punched_out.elapsed_at(utc(11, 15)), None);
+        assert_eq!(punched_out.since(), Some(utc(10, 0)));
+
+        assert_eq!(SheetStatus::Empty.elapsed_at(utc(11, 15)), None);
+        assert_eq!(SheetStatus::Empty.since(), None);
+    }
+
+    #[test]
+    fn formatted_counts() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 30)),
+            completed(utc(11, 0), utc(11, 45)),
+        ]);
+
+        let cases = [
+            (DurationFormat::HoursMinutesSeconds, "2h 15m", "1h 15m"),
+            (DurationFormat::DecimalHours, "2.25h", "1.25h"),
+            (DurationFormat::Minutes, "135m", "75m"),
+            (DurationFormat::Seconds, "8100s", "4500s"),
+        ];
+
+        for (fmt, total, range) in cases {
+            assert_eq!(sheet.total_duration_formatted(fmt), total);
+            assert_eq!(
+                sheet.count_range_formatted(utc(9, 30), utc(11, 15), fmt),
+                range
+            );
+        }
+    }
+
+    #[test]
+    fn punch_out_before_punch_in_leaves_sheet_unchanged() {
+        let mut sheet = Sheet::default();
+        sheet.punch_in_at(utc(9, 0)).unwrap();
+        let before = sheet.clone();
+
+        assert!(matches!(
+            sheet.punch_out_at(utc(8, 50)),
+            Err(SheetError::InvalidTimeRange { start, stop })
+                if start == utc(9, 0) && stop == utc(8, 50)
+        ));
+        assert_eq!(sheet.events, before.events);
+        assert!(sheet.current_event().is_some());
+
+        sheet.punch_out_at(utc(9, 0)).unwrap();
+        assert_eq!(sheet.events, vec![completed(utc(9, 0), utc(9, 0))]);
+    }
+
+    #[test]
+    fn longest_streak_is_interrupted_by_weekend() {
+        // Monday to Friday, then Monday and Tuesday of the following week.
+        let days = [15, 16, 17, 18, 19, 22, 23];
+        let sheet = Sheet::from_events_unchecked(
+            days.iter()
+                .map(|&day| hours_from_noon(date(2024, 1, day), 1))
+                .collect(),
+        );
+
+        assert_eq!(sheet.longest_streak(), 5);
+    }
+
+    #[test]
+    fn longest_streak_resumes_after_gap() {
+        let days = [1, 2, 5, 6, 7, 8, 9, 10, 12];
+        let sheet = Sheet::from_events_unchecked(
+            days.iter()
+                .map(|&day| hours_from_noon(date(2024, 1, day), 1))
+                .collect(),
+        );
+
+        assert_eq!(sheet.longest_streak(), 6);
+        assert_eq!(Sheet::default().longest_streak(), 0);
+    }
+
+    #[test]
+    fn streak_counts_back_from_today() {
+        let today = Local::now().date_naive();
+        let days_ago = |days: &[i64]| {
+            Sheet::from_events_unchecked(
+                days.iter()
+                    .rev()
+                    .map(|&n| hours_from_noon(today - Duration::days(n), 1))
+                    .collect(),
+            )
+        };
+
+        assert_eq!(days_ago(&[0, 1, 2, 4, 5]).streak(), 3);
+        assert_eq!(days_ago(&[1, 2]).streak(), 2);
+        assert_eq!(days_ago(&[2, 3]).streak(), 0);
+        assert_eq!(Sheet::default().streak(), 0);
+    }
+
+    #[test]
+    fn busiest_and_least_productive_days_break_ties_by_earliest() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            hours_from_noon(date(2024, 1, 15), 1),
+            hours_from_noon(date(2024, 1, 16), 3),
+            hours_from_noon(date(2024, 1, 17), 1),
+            hours_from_noon(date(2024, 1, 18), 3),
+            hours_from_noon(date(2024, 1, 19), 2),
+        ]);
+
+        assert_eq!(sheet.busiest_day(), Some(date(2024, 1, 16)));
+        assert_eq!(sheet.least_productive_day(), Some(date(2024, 1, 15)));
+    }
+
+    #[test]
+    fn busiest_and_least_productive_days_of_empty_sheet() {
+        assert_eq!(Sheet::default().busiest_day(), None);
+        assert_eq!(Sheet::default().least_productive_day(), None);
+    }
+
+    #[test]
+    fn with_rounding_rounds_completed_events_only() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 5), utc(10, 10)),
+            Event::new(utc(11, 5)),
+        ]);
+
+        let ceiled = sheet.with_rounding(RoundingMode::CeilMinutes(15));
+        assert_eq!(
+            ceiled.events,
+            vec![completed(utc(9, 15), utc(10, 15)), Event::new(utc(11, 5))]
+        );
+
+        let mut floored = sheet.clone();
+        floored.apply_rounding(RoundingMode::FloorMinutes(15));
+        assert_eq!(
+            floored.events,
+            vec![completed(utc(9, 0), utc(10, 0)), Event::new(utc(11, 5))]
+        );
+
+        assert_eq!(sheet.events[0], completed(utc(9, 5), utc(10, 10)));
+    }
+
+    #[test]
+    fn clamp_to_work_hours_splits_events_spanning_midnight() {
+        let schedule = WorkSchedule::weekdays(time(9, 0), time(17, 0));
+        let sheet = Sheet::from_events_unchecked(vec![completed(
+            local(2024, 1, 15, 16, 0),
+            local(2024, 1, 16, 10, 0),
+        )]);
+
+        assert_eq!(
+            sheet.clamp_to_work_hours(&schedule).events,
+            vec![
+                completed(local(2024, 1, 15, 16, 0), local(2024, 1, 15, 17, 0)),
+                completed(local(2024, 1, 16, 9, 0), local(2024, 1, 16, 10, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn clamp_to_work_hours_with_overnight_schedule() {
+        let schedule = WorkSchedule::weekdays(time(22, 0), time(6, 0));
+        let sheet = Sheet::from_events_unchecked(vec![completed(
+            local(2024, 1, 15, 20, 0),
+            local(2024, 1, 16, 7, 0),
+        )]);
+
+        assert_eq!(
+            sheet.clamp_to_work_hours(&schedule).events,
+            vec![completed(
+                local(2024, 1, 15, 22, 0),
+                local(2024, 1, 16, 6, 0)
+            )]
+        );
+    }
+
+    #[test]
+    fn clamp_to_work_hours_removes_weekends() {
+        let schedule = WorkSchedule::weekdays(time(9, 0), time(17, 0));
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(local(2024, 1, 19, 16, 0), local(2024, 1, 22, 10, 0)),
+            completed(local(2024, 1, 27, 10, 0), local(2024, 1, 27, 12, 0)),
+        ]);
+
+        assert_eq!(
+            sheet.clamp_to_work_hours(&schedule).events,
+            vec![
+                completed(local(2024, 1, 19, 16, 0), local(2024, 1, 19, 17, 0)),
+                completed(local(2024, 1, 22, 9, 0), local(2024, 1, 22, 10, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_table_snapshot() {
+        assert_eq!(
+            table_sheet().to_markdown_table(),
+            "\
+| Date       | Start    | Stop     | Duration | Description              |
+| ---------- | -------- | -------- | -------- | ------------------------ |
+| 2024-01-15 | 09:00:00 | 10:30:00 | 1h 30m   | Tests \\| <docs> and more |
+| 2024-01-15 | 11:00:00 | —        | —        |                          |
+"
+        );
+    }
+
+    #[test]
+    fn markdown_table_of_empty_sheet() {
+        assert_eq!(
+            Sheet::default().to_markdown_table(),
+            "\
+| Date | Start | Stop | Duration | Description |
+| ---- | ----- | ---- | -------- | ----------- |
+"
+        );
+    }
+
+    #[test]
+    fn html_table_is_well_formed() {
+        let html = table_sheet().to_html_table();
+
+        let mut open_tags = Vec::new();
+        for tag in html
+            .split('<')
+            .skip(1)
+            .map(|rest| &rest[..rest.find('>').unwrap()])
+        {
+            match tag.strip_prefix('/') {
+                Some(name) => assert_eq!(open_tags.pop(), Some(name), "unexpected </{}>", name),
+                None => open_tags.push(tag),
+            }
+        }
+        assert!(open_tags.is_empty(), "unclosed tags {:?}", open_tags);
+
+        assert!(html.starts_with("<table>"));
+        assert!(html.contains("<td>Tests | &lt;docs&gt;\nand more</td>"));
+    }
+
+    #[test]
+    fn html_table_includes_every_event() {
+        let sheet = table_sheet();
+        let html = sheet.to_html_table();
+
+        assert_eq!(html.matches("<tr>").count(), sheet.len() + 1);
+        assert_eq!(html.matches("<th>").count(), TABLE_HEADINGS.len());
+        assert_eq!(
+            html.matches("<td>").count(),
+            sheet.len() * TABLE_HEADINGS.len()
+        );
+        for event in &sheet {
+            assert!(html.contains(&format!("<td>{}</td>", event.start.format("%H:%M:%S"))));
+        }
+    }
+
+    #[test]
+    fn write_with_backup_keeps_original_and_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.json");
+
+        let mut old = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        assert_eq!(old.write_to_path_with_backup(&path).unwrap(), None);
+        let old_contents = fs::read_to_string(&path).unwrap();
+
+        let mut new = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+        ]);
+        let backup_path = new
+            .write_to_path_with_backup(&path)
+            .unwrap()
+            .expect("a backup is made of the existing file");
+
+        assert_eq!(backup_path.parent(), Some(dir.path()));
+        let backup_name = backup_path.file_name().unwrap().to_str().unwrap();
+        assert!(backup_name.starts_with("sheet-"));
+        assert!(backup_name.ends_with(".json"));
+
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), old_contents);
+        assert_eq!(Sheet::load_from_path(&path).unwrap(), new);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_write_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.json");
+
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+        sheet.write_to_path_async(&path).await.unwrap();
+
+        assert!(sheet.updated_at().is_some());
+        assert!(!temp_path_for(&path).exists());
+
+        let loaded = Sheet::load_from_path_async(&path).await.unwrap();
+        assert_eq!(loaded, sheet);
+        assert_eq!(loaded.updated_at(), sheet.updated_at());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_load_of_missing_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(matches!(
+            Sheet::load_from_path_async(&dir.path().join("missing.json")).await,
+            Err(SheetError::OpenSheet(_))
+        ));
+    }
+
+    #[test]
+    fn equal_values_hash_identically() {
+        let event = Event::with_description(utc(9, 0), "Write tests");
+        assert_eq!(hash_of(&event), hash_of(&event.clone()));
+
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        let mut tolerant = sheet.clone();
+        tolerant.set_future_tolerance(Duration::hours(1));
+        assert_eq!(hash_of(&sheet), hash_of(&tolerant));
+
+        assert_eq!(
+            hash_of(&SheetStatus::PunchedIn(utc(9, 0))),
+            hash_of(&SheetStatus::PunchedIn(utc(9, 0)))
+        );
+    }
+
+    #[test]
+    fn distinct_values_hash_differently() {
+        assert_ne!(
+            hash_of(&completed(utc(9, 0), utc(10, 0))),
+            hash_of(&completed(utc(9, 0), utc(10, 1)))
+        );
+        assert_ne!(
+            hash_of(&Event::new(utc(9, 0))),
+            hash_of(&Event::with_description(utc(9, 0), "Write tests"))
+        );
+
+        assert_ne!(
+            hash_of(&Sheet::from_events_unchecked(vec![Event::new(utc(9, 0))])),
+            hash_of(&Sheet::default())
+        );
+
+        assert_ne!(
+            hash_of(&SheetStatus::PunchedIn(utc(9, 0))),
+            hash_of(&SheetStatus::PunchedOut(utc(9, 0)))
+        );
+    }
+
+    #[test]
+    fn dedup_removes_adjacent_duplicates() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0)),
+                ..Event::with_description(utc(9, 0), "first")
+            },
+            Event {
+                stop: Some(utc(10, 0)),
+                ..Event::with_description(utc(9, 0), "second")
+            },
+            completed(utc(11, 0), utc(12, 0)),
+        ]);
+        assert!(!sheet.is_deduped());
+
+        sheet.dedup();
+
+        assert!(sheet.is_deduped());
+        assert_eq!(sheet.len(), 2);
+        assert_eq!(sheet.events[0].description.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn dedup_removes_non_adjacent_duplicates() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(13, 0)),
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(13, 0)),
+        ]);
+        assert!(!sheet.is_deduped());
+
+        sheet.dedup();
+
+        assert_eq!(
+            sheet.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(11, 0), utc(12, 0)),
+                Event::new(utc(13, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn dedup_keeps_distinct_events() {
+        let events = vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(9, 0), utc(10, 30)),
+            Event::new(utc(9, 0)),
+        ];
+        let mut sheet = Sheet::from_events_unchecked(events.clone());
+        assert!(sheet.is_deduped());
+
+        sheet.dedup();
+
+        assert_eq!(sheet.len(), events.len());
+        assert!(sheet.is_deduped());
+    }
+
+    #[test]
+    fn retain_nothing_leaves_empty_sheet() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+
+        sheet.retain(|_| false);
+
+        assert!(sheet.is_empty());
+    }
+
+    #[test]
+    fn retain_and_extract_if_split_events() {
+        let events = vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+        ];
+
+        let mut retained = Sheet::from_events_unchecked(events.clone());
+        retained.retain(Event::is_complete);
+        assert_eq!(retained.events, vec![events[0].clone(), events[2].clone()]);
+
+        let mut remaining = Sheet::from_events_unchecked(events.clone());
+        let extracted = remaining.extract_if(Event::is_ongoing);
+        assert_eq!(extracted.events, vec![events[1].clone()]);
+        assert_eq!(remaining, retained);
+    }
+
+    #[test]
+    fn current_event_with_ongoing_event() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+
+        assert_eq!(sheet.current_event(), Some(&Event::new(utc(11, 0))));
+
+        sheet.current_event_mut().unwrap().description = Some("Write tests".to_owned());
+        assert_eq!(
+            sheet.events[1],
+            Event::with_description(utc(11, 0), "Write tests")
+        );
+    }
+
+    #[test]
+    fn current_event_without_ongoing_event() {
+        let mut empty = Sheet::default();
+        assert_eq!(empty.current_event(), None);
+        assert_eq!(empty.current_event_mut(), None);
+
+        let mut completed_only = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+        ]);
+        assert_eq!(completed_only.current_event(), None);
+        assert_eq!(completed_only.current_event_mut(), None);
+    }
+
+    #[test]
+    fn time_since_punch_in_at() {
+        let punched_in = Sheet::from_events_unchecked(vec![
+            completed(utc(8, 0), utc(8, 30)),
+            Event::new(utc(9, 0)),
+        ]);
+        assert_eq!(
+            punched_in.time_since_punch_in_at(utc(10, 15)),
+            Some(Duration::minutes(75))
+        );
+        assert!(punched_in.time_since_punch_in().unwrap() > Duration::minutes(75));
+
+        let punched_out = Sheet::from_events_unchecked(vec![completed(utc(8, 0), utc(8, 30))]);
+        assert_eq!(punched_out.time_since_punch_in_at(utc(10, 15)), None);
+        assert_eq!(Sheet::default().time_since_punch_in_at(utc(10, 15)), None);
+    }
+
+    #[test]
+    fn event_at_time_at_boundaries() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(10, 0), utc(11, 0)),
+            Event::new(utc(12, 0)),
+        ]);
+        let second = Duration::seconds(1);
+
+        assert_eq!(sheet.event_at_time(utc(9, 0) - second), None);
+        assert_eq!(sheet.event_at_time(utc(9, 0)), Some(&sheet.events[0]));
+        assert_eq!(sheet.event_at_time(utc(9, 30)), Some(&sheet.events[0]));
+        assert_eq!(
+            sheet.event_at_time(utc(10, 0) - second),
+            Some(&sheet.events[0])
+        );
+        assert_eq!(sheet.event_at_time(utc(10, 0)), Some(&sheet.events[1]));
+        assert_eq!(sheet.event_at_time(utc(11, 0)), None);
+        assert_eq!(sheet.event_at_time(utc(12, 0) - second), None);
+        assert_eq!(sheet.event_at_time(utc(12, 0)), Some(&sheet.events[2]));
+        assert_eq!(sheet.event_at_time(utc(18, 0)), Some(&sheet.events[2]));
+    }
+
+    #[test]
+    fn clear_leaves_empty_sheet() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(10, 0)),
+            Event::new(utc(11, 0)),
+        ]);
+        assert_eq!(sheet.len(), 2);
+        assert!(!sheet.is_empty());
+
+        sheet.clear();
+
+        assert!(sheet.is_empty());
+        assert_eq!(sheet.len(), 0);
+    }
+
+    #[test]
+    fn last_n_events_at_boundaries() {
+        let events = vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(13, 0)),
+        ];
+        let sheet = Sheet::from_events_unchecked(events.clone());
+
+        for (n, expected) in [
+            (0, &events[3..]),
+            (1, &events[2..]),
+            (3, &events[..]),
+            (5, &events[..]),
+        ] {
+            assert_eq!(sheet.last_n_events(n), expected, "last {} events", n);
+
+            let mut truncated = sheet.clone();
+            truncated.truncate_to_last_n_events(n);
+            assert_eq!(truncated.events, expected, "truncated to {} events", n);
+        }
+    }
+
+    #[test]
+    fn adding_sheets_is_commutative_in_total_duration() {
+        let mut a = Sheet::named("a");
+        a.events = vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(13, 0), utc(13, 45)),
+        ];
+        let mut b = Sheet::named("b");
+        b.events = vec![
+            completed(utc(11, 0), utc(12, 30)),
+            completed(utc(9, 0), utc(10, 0)),
+        ];
+
+        let a_plus_b = a.clone() + b.clone();
+        let b_plus_a = b.clone() + a.clone();
+
+        assert_eq!(a_plus_b.total_duration(), b_plus_a.total_duration());
+        assert_eq!(a_plus_b.total_duration(), Duration::minutes(195));
+        assert_eq!(a_plus_b.name(), Some("a"));
+        assert_eq!(b_plus_a.name(), Some("b"));
+        assert!(a_plus_b.is_sorted());
+
+        let mut assigned = a.clone();
+        assigned += b;
+        assert_eq!(assigned, a_plus_b);
+    }
+
+    #[test]
+    fn from_events_accepts_ordered_events() {
+        let events = vec![
+            completed(utc(9, 0), utc(10, 0)),
+            completed(utc(10, 0), utc(11, 0)),
+            Event::new(utc(12, 0)),
+        ];
+
+        assert_eq!(Sheet::from_events(events.clone()).unwrap().events, events);
+    }
+
+    #[test]
+    fn from_events_rejects_unsorted_events() {
+        let events = vec![
+            completed(utc(11, 0), utc(12, 0)),
+            completed(utc(9, 0), utc(10, 0)),
+        ];
+
+        assert!(matches!(
+            Sheet::from_events(events.clone()),
+            Err(SheetError::OutOfOrder(start)) if start == utc(9, 0)
+        ));
+        assert_eq!(Sheet::from_events_unchecked(events.clone()).events, events);
+    }
+
+    #[test]
+    fn from_events_rejects_overlapping_and_invalid_events() {
+        assert!(matches!(
+            Sheet::from_events(vec![
+                completed(utc(9, 0), utc(10, 30)),
+                completed(utc(10, 0), utc(11, 0)),
+            ]),
+            Err(SheetError::OutOfOrder(_))
+        ));
+        assert!(matches!(
+            Sheet::from_events(vec![Event::new(utc(9, 0)), Event::new(utc(10, 0))]),
+            Err(SheetError::PunchedIn(_))
+        ));
+        assert!(matches!(
+            Sheet::from_events(vec![completed(utc(10, 0), utc(9, 0))]),
+            Err(SheetError::InvalidTimeRange { .. })
+        ));
+    }
+
+    #[test]
+    fn count_range_stops_ongoing_event_at_end() {
+        let now = Utc::now();
+        let mut sheet = Sheet::default();
+        sheet.punch_in_at(now - Duration::hours(3)).unwrap();
+
+        let counted = sheet.count_range(now - Duration::hours(4), now - Duration::hours(1));
+
+        assert_eq!(counted, Duration::hours(2));
+    }
+
+    #[test]
+    fn count_range_never_counts_negative_time() {
+        let now = Utc::now();
+        let future = Sheet::from_events_unchecked(vec![Event::new(now + Duration::hours(1))]);
+
+        assert_eq!(
+            future.count_range(now - Duration::hours(1), now + Duration::hours(2)),
+            Duration::zero()
+        );
+
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+        assert_eq!(sheet.count_range(utc(10, 0), utc(9, 0)), Duration::zero());
+    }
+
+    #[test]
+    fn report_summarises_events_in_range() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(local(2024, 1, 15, 9, 0), local(2024, 1, 15, 12, 0)),
+            completed(local(2024, 1, 15, 13, 0), local(2024, 1, 15, 14, 0)),
+            completed(local(2024, 1, 16, 9, 0), local(2024, 1, 16, 11, 0)),
+        ]);
+
+        let report = sheet.report(local(2024, 1, 15, 0, 0), local(2024, 1, 17, 0, 0));
+
+        assert_eq!(
+            report,
+            SheetReport {
+                total_duration: Duration::hours(6),
+                session_count: 3,
+                working_days: 2,
+                average_per_day: Duration::hours(3),
+                longest_session: Some(Duration::hours(3)),
+                shortest_session: Some(Duration::hours(1)),
+                gaps: vec![Duration::hours(1), Duration::hours(19)],
+                total_break_time: Duration::hours(20),
+            }
+        );
+    }
+
+    #[test]
+    fn report_counts_working_days_of_events_straddling_begin() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(
+            local(2024, 1, 14, 22, 0),
+            local(2024, 1, 15, 2, 0),
+        )]);
+
+        let report = sheet.report(local(2024, 1, 15, 0, 0), local(2024, 1, 16, 0, 0));
+
+        assert_eq!(report.total_duration, Duration::hours(2));
+        assert_eq!(report.working_days, 1);
+        assert_eq!(report.average_per_day, Duration::hours(2));
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_round_trip_preserves_events() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            Event {
+                stop: Some(utc(10, 0)),
+                project: Some("punch-clock".to_owned()),
+                tags: vec!["rust".to_owned(), "tests".to_owned()],
+                ..Event::with_description(utc(9, 0), "Write tests, with commas")
+            },
+            Event::new(utc(11, 0)),
+        ]);
+
+        let mut csv = Vec::new();
+        sheet.to_csv_writer(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+
+        assert!(csv.starts_with("start,stop,project,description,tags\n"));
+        assert_eq!(Sheet::from_csv_reader(csv.as_bytes()).unwrap(), sheet);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_only_requires_start_column() {
+        let csv = "start\n2024-01-15T09:00:00Z\n";
+
+        assert_eq!(
+            Sheet::from_csv_reader(csv.as_bytes()).unwrap().events,
+            vec![Event::new(utc(9, 0))]
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_empty_stop_is_ongoing() {
+        let csv = "start,stop\n2024-01-15T09:00:00Z,2024-01-15T10:00:00Z\n2024-01-15T11:00:00Z,\n";
+
+        assert_eq!(
+            Sheet::from_csv_reader(csv.as_bytes()).unwrap().events,
+            vec![completed(utc(9, 0), utc(10, 0)), Event::new(utc(11, 0))]
+        );
+    }
+
+    #[test]
+    fn punch_out_on_unsorted_sheet_is_out_of_order() {
+        let mut sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(11, 0), utc(12, 0)),
+            Event::new(utc(9, 0)),
+        ]);
+        let events = sheet.events.clone();
+
+        assert!(matches!(
+            sheet.punch_out_at(utc(10, 0)),
+            Err(SheetError::OutOfOrder(time)) if time == utc(10, 0)
+        ));
+        assert_eq!(sheet.events, events);
+    }
+
+    #[test]
+    fn push_event_appends_after_last_event() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        sheet.push_event(completed(utc(10, 0), utc(11, 0))).unwrap();
+        sheet.push_event(Event::new(utc(12, 0))).unwrap();
+
+        assert_eq!(
+            sheet.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(10, 0), utc(11, 0)),
+                Event::new(utc(12, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn push_event_rejects_event_starting_before_last_stops() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert!(matches!(
+            sheet.push_event(completed(utc(9, 30), utc(11, 0))),
+            Err(SheetError::OutOfOrder(start)) if start == utc(9, 30)
+        ));
+        assert_eq!(sheet.events, vec![completed(utc(9, 0), utc(10, 0))]);
+    }
+
+    #[test]
+    fn push_event_rejects_event_stopping_before_it_starts() {
+        let mut sheet = Sheet::default();
+
+        assert!(matches!(
+            sheet.push_event(completed(utc(11, 0), utc(10, 0))),
+            Err(SheetError::InvalidTimeRange { start, stop })
+                if start == utc(11, 0) && stop == utc(10, 0)
+        ));
+        assert!(sheet.is_empty());
+    }
+
+    #[test]
+    fn push_event_rejects_event_after_ongoing_event() {
+        let mut sheet = Sheet::from_events_unchecked(vec![Event::new(utc(9, 0))]);
+
+        assert!(matches!(
+            sheet.push_event(completed(utc(10, 0), utc(11, 0))),
+            Err(SheetError::PunchedIn(start)) if start == utc(9, 0)
+        ));
+        assert_eq!(sheet.events, vec![Event::new(utc(9, 0))]);
+    }
+
+    #[test]
+    fn punch_beyond_future_tolerance_is_rejected() {
+        let mut sheet = Sheet::default();
+        let time = Utc::now() + Duration::minutes(5);
+
+        assert!(matches!(
+            sheet.punch_in_at(time),
+            Err(SheetError::FutureTime(future)) if future == time
+        ));
+        assert!(sheet.is_empty());
+
+        sheet.set_future_tolerance(Duration::hours(1));
+        assert_eq!(sheet.punch_in_at(time).unwrap(), time);
+    }
+
+    #[test]
+    fn punch_within_future_tolerance_is_accepted() {
+        let mut sheet = Sheet::default();
+        let start = Utc::now() + Duration::seconds(30);
+
+        assert_eq!(sheet.punch_in_at(start).unwrap(), start);
+        assert_eq!(
+            sheet.punch_out_at(start + Duration::seconds(1)).unwrap(),
+            start + Duration::seconds(1)
+        );
+    }
+
+    #[test]
+    fn zero_future_tolerance_rejects_any_future_punch() {
+        let mut sheet = Sheet::default();
+        sheet.set_future_tolerance(Duration::zero());
+
+        let future = Utc::now() + Duration::seconds(5);
+        assert!(matches!(
+            sheet.punch_in_at(future),
+            Err(SheetError::FutureTime(time)) if time == future
+        ));
+
+        let past = Utc::now() - Duration::seconds(1);
+        assert_eq!(sheet.punch_in_at(past).unwrap(), past);
+    }
+
+    #[test]
+    fn normalize_clipping_invalid_events() {
+        let mut sheet = hand_edited_sheet();
+
+        assert_eq!(
+            sheet.normalize(NormalizeMode::ClipInvalid),
+            NormalizeReport {
+                removed: 0,
+                merged: 1,
+                clipped: 1,
+            }
+        );
+        assert_eq!(
+            sheet.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(11, 0), utc(12, 0)),
+                completed(utc(14, 0), utc(14, 0)),
+            ]
+        );
+
+        let normalized = sheet.clone();
+        assert_eq!(
+            sheet.normalize(NormalizeMode::ClipInvalid),
+            NormalizeReport::default()
+        );
+        assert_eq!(sheet, normalized);
+    }
+
+    #[test]
+    fn normalize_removing_invalid_events() {
+        let mut sheet = hand_edited_sheet();
+
+        assert_eq!(
+            sheet.normalize(NormalizeMode::RemoveInvalid),
+            NormalizeReport {
+                removed: 1,
+                merged: 1,
+                clipped: 0,
+            }
+        );
+        assert_eq!(
+            sheet.events,
+            vec![
+                completed(utc(9, 0), utc(10, 0)),
+                completed(utc(11, 0), utc(12, 0)),
+            ]
+        );
+
+        let normalized = sheet.clone();
+        assert_eq!(
+            sheet.normalize(NormalizeMode::RemoveInvalid),
+            NormalizeReport::default()
+        );
+        assert_eq!(sheet, normalized);
+    }
+
+    #[test]
+    fn adding_events_appends_them() {
+        let mut sheet = Sheet::default() + completed(utc(9, 0), utc(10, 0));
+        sheet += Event::new(utc(11, 0));
+
+        assert_eq!(
+            sheet.events,
+            vec![completed(utc(9, 0), utc(10, 0)), Event::new(utc(11, 0))]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unable to add event to sheet")]
+    fn add_assigning_overlapping_event_panics() {
+        let mut sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        sheet += completed(utc(9, 30), utc(11, 0));
+    }
+
+    #[test]
+    fn try_add_rejects_overlapping_event() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(9, 0), utc(10, 0))]);
+
+        assert!(matches!(
+            sheet.try_add(completed(utc(9, 30), utc(11, 0))),
+            Err(SheetError::OutOfOrder(start)) if start == utc(9, 30)
+        ));
+    }
 }
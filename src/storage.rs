@@ -0,0 +1,52 @@
+//! Abstracting over where recorded timesheets are stored.
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{sheet::SheetError, Sheet};
+
+/// A location that a sheet can be loaded from and stored to.
+pub trait StorageBackend {
+    /// Attempt to load the sheet held by this backend.
+    fn load(&self) -> Result<Sheet, SheetError>;
+
+    /// Attempt to store the given sheet in this backend, replacing any sheet already held.
+    fn store(&self, sheet: &Sheet) -> Result<(), SheetError>;
+}
+
+/// Stores a sheet as JSON in the file at the given path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileBackend(pub PathBuf);
+
+impl StorageBackend for FileBackend {
+    fn load(&self) -> Result<Sheet, SheetError> {
+        Sheet::load_from_path(&self.0)
+    }
+
+    fn store(&self, sheet: &Sheet) -> Result<(), SheetError> {
+        sheet.store_at_path(&self.0)
+    }
+}
+
+/// Stores a sheet in memory, shared between all clones of the backend.
+///
+/// Loading from a backend that has never had a sheet stored in it returns an empty sheet.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBackend(pub Arc<Mutex<Option<Sheet>>>);
+
+impl StorageBackend for MemoryBackend {
+    fn load(&self) -> Result<Sheet, SheetError> {
+        let stored = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        Ok(stored.clone().unwrap_or_default())
+    }
+
+    fn store(&self, sheet: &Sheet) -> Result<(), SheetError> {
+        let mut stored = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        *stored = Some(sheet.clone());
+
+        Ok(())
+    }
+}
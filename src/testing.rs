@@ -0,0 +1,36 @@
+//! Helpers for building the times, dates and events used in tests.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::Event;
+
+/// The given time on Monday 2024-01-15, in UTC.
+pub(crate) fn utc(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, hour, minute, 0).unwrap()
+}
+
+/// The given local time, in UTC.
+pub(crate) fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Local
+        .with_ymd_and_hms(year, month, day, hour, minute, 0)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+/// The given date.
+pub(crate) fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// The given time of day.
+pub(crate) fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// An event that started and stopped at the given times.
+pub(crate) fn completed(start: DateTime<Utc>, stop: DateTime<Utc>) -> Event {
+    Event {
+        stop: Some(stop),
+        ..Event::new(start)
+    }
+}
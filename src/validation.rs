@@ -0,0 +1,182 @@
+//! Checking recorded timesheets against configurable rules.
+
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+
+use crate::{format::format_duration, Sheet};
+
+/// A constraint that the events in a sheet should satisfy.
+pub trait ValidationRule {
+    /// Check the given sheet against this rule, returning every violation found.
+    fn check(&self, sheet: &Sheet) -> Vec<ValidationError>;
+}
+
+/// Ways in which a sheet can violate a [`ValidationRule`][rule].
+///
+/// Events are identified by their index in [`Sheet::events`][events].
+///
+/// [rule]: ./trait.ValidationRule.html
+/// [events]: ../sheet/struct.Sheet.html#structfield.events
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("events at indices {0} and {1} overlap")]
+    Overlapping(usize, usize),
+    #[error("event at index {index} extends into the future, to {time}")]
+    FutureEvent { index: usize, time: DateTime<Utc> },
+    #[error(
+        "event at index {index} lasts {}, longer than the maximum of {}",
+        format_duration(*duration),
+        format_duration(*max)
+    )]
+    SessionTooLong {
+        index: usize,
+        duration: Duration,
+        max: Duration,
+    },
+    #[error(
+        "break of {} between events at indices {before} and {after} is shorter than the minimum \
+         of {}",
+        format_duration(*duration),
+        format_duration(*min)
+    )]
+    BreakTooShort {
+        before: usize,
+        after: usize,
+        duration: Duration,
+        min: Duration,
+    },
+}
+
+/// Requires that no two events overlap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoOverlaps;
+
+impl ValidationRule for NoOverlaps {
+    fn check(&self, sheet: &Sheet) -> Vec<ValidationError> {
+        sheet
+            .find_overlaps()
+            .into_iter()
+            .map(|(a, b)| ValidationError::Overlapping(a, b))
+            .collect()
+    }
+}
+
+/// Requires that no event starts or stops after the current time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoFutureEvents;
+
+impl ValidationRule for NoFutureEvents {
+    fn check(&self, sheet: &Sheet) -> Vec<ValidationError> {
+        let now = Utc::now();
+
+        sheet
+            .events
+            .iter()
+            .enumerate()
+            .filter_map(|(index, e)| {
+                let latest = e.stop.map_or(e.start, |stop| stop.max(e.start));
+
+                if latest > now {
+                    Some(ValidationError::FutureEvent {
+                        index,
+                        time: latest,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Requires that no event lasts longer than the given duration, including an ongoing
+/// time-tracking period if there is one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxSessionDuration(pub Duration);
+
+impl ValidationRule for MaxSessionDuration {
+    fn check(&self, sheet: &Sheet) -> Vec<ValidationError> {
+        sheet
+            .events
+            .iter()
+            .enumerate()
+            .filter_map(|(index, e)| {
+                let duration = e.duration();
+
+                if duration > self.0 {
+                    Some(ValidationError::SessionTooLong {
+                        index,
+                        duration,
+                        max: self.0,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Requires that the time between the end of one event and the start of the next is at least the
+/// given duration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinBreakBetweenSessions(pub Duration);
+
+impl ValidationRule for MinBreakBetweenSessions {
+    fn check(&self, sheet: &Sheet) -> Vec<ValidationError> {
+        sheet
+            .events
+            .windows(2)
+            .enumerate()
+            .filter_map(|(before, pair)| {
+                let duration = pair[1].start - pair[0].stop?;
+
+                if duration < self.0 {
+                    Some(ValidationError::BreakTooShort {
+                        before,
+                        after: before + 1,
+                        duration,
+                        min: self.0,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{completed, utc};
+
+    #[test]
+    fn session_too_long_formats_durations() {
+        let sheet = Sheet::from_events_unchecked(vec![completed(utc(8, 0), utc(18, 0))]);
+
+        let errors = MaxSessionDuration(Duration::hours(8)).check(&sheet);
+
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["event at index 0 lasts 10h, longer than the maximum of 8h"]
+        );
+    }
+
+    #[test]
+    fn break_too_short_formats_durations() {
+        let sheet = Sheet::from_events_unchecked(vec![
+            completed(utc(9, 0), utc(12, 0)),
+            completed(utc(12, 10), utc(13, 0)),
+        ]);
+
+        let errors = MinBreakBetweenSessions(Duration::minutes(30)).check(&sheet);
+
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "break of 10m between events at indices 0 and 1 is shorter than the minimum of 30m"
+            ]
+        );
+    }
+}